@@ -0,0 +1,143 @@
+#![allow(dead_code)]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// The allocator has to be picked at compile time, so it's always installed - it only counts once
+// it's enabled, and until then costs one atomic load per allocation.
+pub struct CountingAllocator;
+
+static IS_ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+fn count_allocation(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES_ALLOCATED.fetch_add(size, Ordering::Relaxed);
+
+    let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+}
+
+fn count_deallocation(size: usize) {
+    // Saturating, since whatever was allocated before counting started can still be freed.
+    let _ = CURRENT_BYTES.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+        Some(current.saturating_sub(size))
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if IS_ENABLED.load(Ordering::Relaxed) {
+            count_allocation(layout.size());
+        }
+
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if IS_ENABLED.load(Ordering::Relaxed) {
+            count_allocation(layout.size());
+        }
+
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if IS_ENABLED.load(Ordering::Relaxed) {
+            count_deallocation(layout.size());
+        }
+
+        System.dealloc(ptr, layout)
+    }
+
+    // A realloc counts as a fresh allocation of the new size, since that's usually what it is.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if IS_ENABLED.load(Ordering::Relaxed) {
+            count_deallocation(layout.size());
+            count_allocation(new_size);
+        }
+
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocationStats {
+    pub allocations: usize,
+    pub bytes_allocated: usize,
+    pub peak_bytes: usize,
+}
+
+impl fmt::Display for AllocationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.bytes_allocated),
+            format_bytes(self.peak_bytes)
+        )
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{bytes}B"),
+        1024..1048576 => format!("{:.1}KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MiB", bytes as f64 / 1048576.0),
+    }
+}
+
+pub fn enable() {
+    IS_ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    IS_ENABLED.load(Ordering::Relaxed)
+}
+
+// Starts counting from scratch. The peak starts from whatever is still allocated.
+pub fn reset() {
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    BYTES_ALLOCATED.store(0, Ordering::Relaxed);
+    PEAK_BYTES.store(CURRENT_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+pub fn get_stats() -> AllocationStats {
+    AllocationStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!("1023B", format_bytes(1023));
+        assert_eq!("1.5KiB", format_bytes(1536));
+        assert_eq!("2.0MiB", format_bytes(2 * 1048576));
+    }
+
+    #[test]
+    fn test_counting() {
+        enable();
+        reset();
+
+        let numbers = vec![0u64; 1000];
+        let stats = get_stats();
+
+        // Other tests run at the same time, so there could be more.
+        assert!(stats.allocations >= 1);
+        assert!(stats.bytes_allocated >= 8000);
+        assert!(stats.peak_bytes >= 8000);
+
+        drop(numbers);
+    }
+}
@@ -0,0 +1,141 @@
+#![allow(dead_code)]
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::render_utilities::Canvas;
+
+// Collects frames of a simulation, keeping only every `stride`-th step so long runs stay small.
+pub struct Recorder {
+    pub fps: usize,
+    stride: usize,
+    frames: Vec<Canvas>,
+}
+
+impl Recorder {
+    pub fn new(fps: usize, stride: usize) -> Self {
+        Self {
+            fps: fps.max(1),
+            stride: stride.max(1),
+            frames: vec![],
+        }
+    }
+
+    // The frame is only built if the step is actually kept.
+    pub fn record<F>(&mut self, step: usize, get_frame: F)
+    where
+        F: FnOnce() -> Canvas,
+    {
+        if step.is_multiple_of(self.stride) {
+            self.frames.push(get_frame());
+        }
+    }
+
+    // For the frames we want regardless of the stride, like the final state.
+    pub fn record_always(&mut self, frame: Canvas) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &[Canvas] {
+        &self.frames
+    }
+
+    pub fn to_animated_svg(&self, scale: usize) -> String {
+        let Some(first_frame) = self.frames.first() else {
+            return String::new();
+        };
+
+        let frame_count = self.frames.len();
+        let duration = frame_count as f64 / self.fps as f64;
+
+        let mut lines = vec![first_frame.get_svg_header(scale)];
+
+        // Every frame is a group that's only visible during its own slice of the loop.
+        for (index, frame) in self.frames.iter().enumerate() {
+            let start = index as f64 / frame_count as f64;
+            let end = (index + 1) as f64 / frame_count as f64;
+
+            let (values, key_times) = match (index, index == frame_count - 1) {
+                (0, true) => ("visible".to_string(), "0".to_string()),
+                (0, false) => ("visible;hidden".to_string(), format!("0;{end}")),
+                (_, true) => ("hidden;visible".to_string(), format!("0;{start}")),
+                (_, false) => (
+                    "hidden;visible;hidden".to_string(),
+                    format!("0;{start};{end}"),
+                ),
+            };
+
+            lines.push(format!(
+                r#"<g visibility="{}">"#,
+                if index == 0 { "visible" } else { "hidden" }
+            ));
+            lines.push(format!(
+                r#"<animate attributeName="visibility" values="{values}" keyTimes="{key_times}" dur="{duration}s" calcMode="discrete" repeatCount="indefinite"/>"#
+            ));
+            lines.extend(frame.get_svg_body(scale));
+            lines.push("</g>".to_string());
+        }
+
+        lines.push("</svg>".to_string());
+        lines.join("\n")
+    }
+
+    // An .svg path gets a single animated file, anything else is a folder of numbered PPM frames.
+    pub fn save<P>(&self, path: P, scale: usize)
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if path.extension().and_then(|extension| extension.to_str()) == Some("svg") {
+            fs::write(path, self.to_animated_svg(scale)).unwrap();
+            return;
+        }
+
+        fs::create_dir_all(path).unwrap();
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            frame.save(path.join(format!("frame_{index:05}.ppm")), scale);
+        }
+    }
+
+    pub fn play_in_terminal(&self) {
+        let frame_duration = Duration::from_secs_f64(1.0 / self.fps as f64);
+        let mut stdout = io::stdout();
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            // Clear the screen and go back to the top left corner before every frame.
+            writeln!(stdout, "\x1b[2J\x1b[H{}", frame.to_terminal()).unwrap();
+            writeln!(stdout, "Frame {} of {}", index + 1, self.frames.len()).unwrap();
+            stdout.flush().unwrap();
+
+            thread::sleep(frame_duration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map_utilities::Point;
+    use crate::render_utilities::{BLACK, WHITE};
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = Recorder::new(10, 2);
+
+        for step in 0..5 {
+            recorder.record(step, || {
+                Canvas::from_points(3, 1, [Point::new(0, step as isize / 2)], WHITE, BLACK)
+            });
+        }
+
+        assert_eq!(3, recorder.frames().len());
+
+        let svg = recorder.to_animated_svg(1);
+        assert_eq!(3, svg.matches("<animate ").count());
+        assert!(svg.contains(r#"values="hidden;visible;hidden" keyTimes="0;0.3333333333333333;0.6666666666666666" dur="0.3s""#));
+    }
+}
@@ -0,0 +1,139 @@
+#![allow(dead_code)]
+use crate::file_utilities::DEFAULT_YEAR;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Arguments {
+    pub year: u32,
+    pub day: u32,
+    pub is_test: bool,
+    pub is_repl: bool,
+    pub is_counting_allocations: bool,
+    pub verbosity: usize,
+    pub traced_targets: Vec<String>,
+    pub render_path: Option<String>,
+    pub export_path: Option<String>,
+    pub animation_path: Option<String>,
+    pub is_playing: bool,
+    pub fps: usize,
+}
+
+impl Default for Arguments {
+    fn default() -> Self {
+        Self {
+            year: DEFAULT_YEAR,
+            day: 25,
+            is_test: false,
+            is_repl: false,
+            is_counting_allocations: false,
+            verbosity: 0,
+            traced_targets: vec![],
+            render_path: None,
+            export_path: None,
+            animation_path: None,
+            is_playing: false,
+            fps: 10,
+        }
+    }
+}
+
+fn get_value<I>(arguments: &mut I, flag: &str) -> String
+where
+    I: Iterator<Item = String>,
+{
+    arguments
+        .next()
+        .unwrap_or_else(|| panic!("{flag} needs a value..."))
+}
+
+// [repl] --year N (or -y N), --day N (or -d N), --test, --allocations, -v/-vv/-vvv (or --verbose, repeated),
+// --trace TARGET (repeated), --render PATH, --export PATH, --animate PATH, --play, --fps N.
+pub fn parse_arguments<I>(arguments: I) -> Arguments
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut result = Arguments::default();
+    let mut arguments = arguments.into_iter();
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "repl" => result.is_repl = true,
+            "--year" | "-y" => {
+                result.year = get_value(&mut arguments, &argument)
+                    .parse()
+                    .expect("The year should be a number!");
+            }
+            "--day" | "-d" => {
+                result.day = get_value(&mut arguments, &argument)
+                    .parse()
+                    .expect("The day should be a number!");
+            }
+            "--test" | "-t" => result.is_test = true,
+            "--allocations" | "-a" => result.is_counting_allocations = true,
+            "--verbose" => result.verbosity += 1,
+            "--trace" => result
+                .traced_targets
+                .push(get_value(&mut arguments, &argument)),
+            "--render" => result.render_path = Some(get_value(&mut arguments, &argument)),
+            "--export" => result.export_path = Some(get_value(&mut arguments, &argument)),
+            "--animate" => result.animation_path = Some(get_value(&mut arguments, &argument)),
+            "--play" => result.is_playing = true,
+            "--fps" => {
+                result.fps = get_value(&mut arguments, &argument)
+                    .parse()
+                    .expect("The frame rate should be a number!");
+            }
+            flag if flag.len() > 1
+                && flag.starts_with('-')
+                && flag[1..].chars().all(|c| c == 'v') =>
+            {
+                result.verbosity += flag.len() - 1;
+            }
+            _ => panic!("I don't know what to do with {argument}..."),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &str) -> Arguments {
+        parse_arguments(arguments.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        assert_eq!(Arguments::default(), parse(""));
+
+        let arguments = parse("--day 17 --test -vv --trace day17 --trace day_24");
+
+        assert_eq!(2024, arguments.year);
+        assert_eq!(17, arguments.day);
+        assert!(arguments.is_test);
+        assert!(!arguments.is_repl);
+        assert!(!arguments.is_counting_allocations);
+        assert_eq!(2, arguments.verbosity);
+        assert_eq!(vec!["day17", "day_24"], arguments.traced_targets);
+
+        assert_eq!(3, parse("-v --verbose -v").verbosity);
+        assert!(parse("repl --day 24").is_repl);
+        assert_eq!(2023, parse("-y 2023 -d 1").year);
+        assert!(parse("-d 10 --allocations").is_counting_allocations);
+        assert_eq!(
+            Some("day_6.svg".to_string()),
+            parse("-d 6 --render day_6.svg").render_path
+        );
+        assert_eq!(
+            Some("day_24.dot".to_string()),
+            parse("-d 24 --export day_24.dot").export_path
+        );
+
+        let arguments = parse("-d 14 --animate frames --play --fps 30");
+
+        assert_eq!(Some("frames".to_string()), arguments.animation_path);
+        assert!(arguments.is_playing);
+        assert_eq!(30, arguments.fps);
+    }
+}
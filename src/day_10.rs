@@ -48,7 +48,7 @@ fn get_trails_per_origin(
         })
         .collect::<VecDeque<_>>();
 
-    // println!("Starting queue {queue:?}");
+    debug!("Starting queue {queue:?}");
 
     while let Some((current_i, current_j, current_h, current_trail)) = queue.pop_front() {
         for (delta_i, delta_j) in &neighbours {
@@ -75,7 +75,7 @@ fn get_trails_per_origin(
 
             if next_h == 9 {
                 let origin = next_trail[0];
-                // println!("New trail found {next_trail:?}");
+                trace!("New trail found {next_trail:?}");
 
                 let values = trails.entry(origin).or_default();
                 values.push(next_trail);
@@ -1,4 +1,8 @@
+use crate::animation_utilities::Recorder;
 use crate::file_utilities::read_lines;
+use crate::map_utilities::{Bounds, Point, Topology};
+use crate::render_utilities::{Canvas, BLACK, GREEN};
+use crate::repl_utilities::{parse_argument, run_interactive, Session};
 use itertools::Itertools;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -22,7 +26,7 @@ fn parse_line_to_robot(line: String) -> Robot {
         .map(|n| n.parse::<i64>().unwrap())
         .collect::<Vec<_>>();
 
-    // println!("position: {position:?}, velocity: {velocity:?}");
+    trace!("position: {position:?}, velocity: {velocity:?}");
 
     Robot {
         position: (position[0], position[1]),
@@ -46,70 +50,68 @@ pub fn run(file_path: String, part: i32) -> usize {
     }
 }
 
-fn print_robots(positions: &Vec<(i64, i64)>, max_x: i64, max_y: i64) {
-    let max_x = max_x as usize;
-    let max_y = max_y as usize;
+// The robots don't tell us how big the room is, so that's straight from the puzzle.
+fn get_bounds(file_path: &str) -> Bounds {
+    if file_path.contains("test") {
+        Bounds::new(11, 7, Topology::Torus)
+    } else {
+        Bounds::new(101, 103, Topology::Torus)
+    }
+}
 
-    let mut map = (0..=max_y)
-        .map(|_| (0..=max_x).map(|_| '.').collect::<Vec<_>>())
+fn get_picture(positions: &[Point], bounds: &Bounds) -> String {
+    let mut map = (0..bounds.height)
+        .map(|_| (0..bounds.width).map(|_| '.').collect::<Vec<_>>())
         .collect::<Vec<_>>();
 
-    for (x, y) in positions {
-        let x = *x as usize;
-        let y = *y as usize;
-
-        let current = map[y][x];
+    for position in positions {
+        let current = map[position.row()][position.column()];
         match current {
-            '.' => map[y][x] = '1',
-            other => map[y][x] = ((other as u8) + 1) as char,
+            '.' => map[position.row()][position.column()] = '1',
+            other => map[position.row()][position.column()] = ((other as u8) + 1) as char,
         }
     }
 
-    for row in map {
-        println!("{}", row.iter().collect::<String>());
-    }
+    map.into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .join("\n")
 }
 
-fn get_robots_after_seconds(
-    robots: &[Robot],
-    max_x: i64,
-    max_y: i64,
-    seconds: usize,
-) -> Vec<(i64, i64)> {
+fn get_canvas(positions: &[Point], bounds: &Bounds) -> Canvas {
+    Canvas::from_points(
+        bounds.width,
+        bounds.height,
+        positions.iter().copied(),
+        GREEN,
+        BLACK,
+    )
+}
+
+fn get_robots_after_seconds(robots: &[Robot], bounds: &Bounds, seconds: usize) -> Vec<Point> {
     let seconds = seconds as i64;
 
     robots
         .iter()
         .copied()
         .map(|robot| {
-            (
-                robot.position.0 + robot.velocity.0 * seconds,
-                robot.position.1 + robot.velocity.1 * seconds,
+            Point::new(
+                (robot.position.1 + robot.velocity.1 * seconds) as isize,
+                (robot.position.0 + robot.velocity.0 * seconds) as isize,
             )
         })
-        .map(|(x, y)| (x.rem_euclid(max_x + 1), y.rem_euclid(max_y + 1)))
+        .map(|point| bounds.wrap(point).unwrap())
         .collect::<Vec<_>>()
 }
 
-fn part_1(file_path: String) -> usize {
-    let robots = parse_data(file_path);
-
-    let robot_positions = robots
-        .iter()
-        .map(|robot| robot.position)
-        .collect::<Vec<_>>();
-
-    let max_x = *robot_positions.iter().map(|(x, _)| x).max().unwrap();
-    let max_y = *robot_positions.iter().map(|(_, y)| y).max().unwrap();
-
-    let robots_after_100_seconds = get_robots_after_seconds(&robots, max_x, max_y, 100);
-
-    let quadrant_x = max_x / 2;
-    let quadrant_y = max_y / 2;
+fn get_safety_factor(positions: Vec<Point>, bounds: &Bounds) -> usize {
+    let quadrant_x = (bounds.width / 2) as isize;
+    let quadrant_y = (bounds.height / 2) as isize;
 
     let mut quadrants = vec![vec![0, 0], vec![0, 0]];
 
-    for (x, y) in robots_after_100_seconds.into_iter() {
+    for position in positions.into_iter() {
+        let (x, y) = (position.column, position.row);
+
         if x == quadrant_x || y == quadrant_y {
             continue;
         }
@@ -123,27 +125,116 @@ fn part_1(file_path: String) -> usize {
     quadrants.into_iter().flatten().product()
 }
 
-fn part_2(file_path: String) -> usize {
+fn part_1(file_path: String) -> usize {
+    let bounds = get_bounds(&file_path);
     let robots = parse_data(file_path);
 
-    let robot_positions = robots
-        .iter()
-        .map(|robot| robot.position)
-        .collect::<Vec<_>>();
+    let robots_after_100_seconds = get_robots_after_seconds(&robots, &bounds, 100);
 
-    let max_x = *robot_positions.iter().map(|(x, _)| x).max().unwrap();
-    let max_y = *robot_positions.iter().map(|(_, y)| y).max().unwrap();
+    get_safety_factor(robots_after_100_seconds, &bounds)
+}
 
+fn find_tree(
+    robots: &[Robot],
+    bounds: &Bounds,
+    mut recorder: Option<&mut Recorder>,
+) -> Option<(usize, Vec<Point>)> {
     for seconds in 0..20000 {
-        let robots_after_seconds = get_robots_after_seconds(&robots, max_x, max_y, seconds);
+        let robots_after_seconds = get_robots_after_seconds(robots, bounds, seconds);
+
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(seconds, || get_canvas(&robots_after_seconds, bounds));
+        }
 
         if robots_after_seconds.iter().unique().count() == robots_after_seconds.len() {
-            print_robots(&robots_after_seconds, max_x, max_y);
-            return seconds;
+            return Some((seconds, robots_after_seconds));
+        }
+    }
+
+    None
+}
+
+pub fn render(file_path: String, output_path: String) {
+    let bounds = get_bounds(&file_path);
+    let robots = parse_data(file_path);
+
+    let (_, positions) = find_tree(&robots, &bounds, None).unwrap();
+
+    get_canvas(&positions, &bounds).save(output_path, 4);
+}
+
+// Every 100th second on the way, and then the tree itself.
+pub fn animate(file_path: String, fps: usize) -> Recorder {
+    let bounds = get_bounds(&file_path);
+    let robots = parse_data(file_path);
+
+    let mut recorder = Recorder::new(fps, 100);
+
+    if let Some((_, positions)) = find_tree(&robots, &bounds, Some(&mut recorder)) {
+        recorder.record_always(get_canvas(&positions, &bounds));
+    }
+
+    recorder
+}
+
+fn part_2(file_path: String) -> usize {
+    let bounds = get_bounds(&file_path);
+    let robots = parse_data(file_path);
+
+    match find_tree(&robots, &bounds, None) {
+        Some((seconds, robots_after_seconds)) => {
+            info!("\n{}", get_picture(&robots_after_seconds, &bounds));
+            seconds
+        }
+        None => 0,
+    }
+}
+
+struct RobotSession {
+    robots: Vec<Robot>,
+    bounds: Bounds,
+}
+
+impl Session for RobotSession {
+    fn get_help(&self) -> Vec<&'static str> {
+        vec![
+            "count - shows how many robots there are",
+            "show <seconds> - draws the robots after that many seconds",
+            "safety <seconds> - the safety factor after that many seconds",
+            "tree - how many seconds until the robots draw a tree",
+        ]
+    }
+
+    fn handle(&mut self, command: &str, arguments: &[&str]) -> Result<String, String> {
+        match command {
+            "count" => Ok(self.robots.len().to_string()),
+            "show" => {
+                let seconds = parse_argument(arguments, 0, "seconds")?;
+                let positions = get_robots_after_seconds(&self.robots, &self.bounds, seconds);
+
+                Ok(get_picture(&positions, &self.bounds))
+            }
+            "safety" => {
+                let seconds = parse_argument(arguments, 0, "seconds")?;
+                let positions = get_robots_after_seconds(&self.robots, &self.bounds, seconds);
+
+                Ok(get_safety_factor(positions, &self.bounds).to_string())
+            }
+            "tree" => match find_tree(&self.robots, &self.bounds, None) {
+                Some((seconds, _)) => Ok(seconds.to_string()),
+                None => Err("The robots never draw a tree.".to_string()),
+            },
+            _ => Err(format!("Day 14 doesn't know how to {command}.")),
         }
     }
+}
+
+#[allow(dead_code)]
+pub fn repl(file_path: String) {
+    let bounds = get_bounds(&file_path);
+    let robots = parse_data(file_path);
 
-    0
+    run_interactive(&mut RobotSession { robots, bounds });
 }
 
 #[cfg(test)]
@@ -1,6 +1,10 @@
+use crate::animation_utilities::Recorder;
 use crate::file_utilities::read_two_chunks;
-use crate::map_utilities::{Direction, Point};
+use crate::map_utilities::{Direction, Grid, Point};
+use crate::render_utilities::{Canvas, Colour, GREY, RED, WHITE, YELLOW};
+use crate::repl_utilities::{parse_optional_argument, run_interactive, Session};
 use std::collections::HashSet;
+use std::fmt;
 
 use itertools::Itertools;
 
@@ -37,7 +41,18 @@ impl Thing {
         }
     }
 
-    fn to_char(&self) -> char {
+    fn to_colour(self) -> Colour {
+        match self {
+            Thing::Wall => GREY,
+            Thing::Box | Thing::LeftBox => YELLOW,
+            // Slightly darker, so neighbouring big boxes can be told apart.
+            Thing::RightBox => Colour::new(200, 150, 30),
+            Thing::Robot => RED,
+            Thing::Empty => WHITE,
+        }
+    }
+
+    fn to_char(self) -> char {
         match self {
             Thing::Wall => '#',
             Thing::Box => 'O',
@@ -103,11 +118,17 @@ impl Map {
         result
     }
 
-    fn print_to_console(&self) {
-        for row in &self.map {
-            let to_print = row.iter().map(Thing::to_char).join("");
-            println!("{to_print}");
-        }
+    fn get_gps_sum(&self) -> usize {
+        self.get_boxes()
+            .into_iter()
+            .map(|point| (point.row * 100) as usize + point.column as usize)
+            .sum()
+    }
+
+    fn to_canvas(&self) -> Canvas {
+        Canvas::from_grid(&Grid::from_rows(self.map.clone()), |thing| {
+            thing.to_colour()
+        })
     }
 
     fn move_robot(&mut self, direction: Direction) {
@@ -126,45 +147,39 @@ impl Map {
                 let spot_thing = self.map[new_spot.row()][new_spot.column()];
 
                 match direction {
-                    Direction::Right | Direction::Left => {
-                        match spot_thing {
-                            Thing::Wall => {
-                                // println!("There's nothing we can do, {new_spot:?} is a wall!");
-                                return;
-                            }
-                            Thing::Empty => {
-                                continue;
-                            }
-                            _ => {
-                                new_spots_to_move.insert(new_spot);
-                            }
+                    Direction::Right | Direction::Left => match spot_thing {
+                        Thing::Wall => {
+                            trace!("There's nothing we can do, {new_spot:?} is a wall!");
+                            return;
                         }
-                    }
-                    Direction::Up | Direction::Down => {
-                        match spot_thing {
-                            Thing::Wall => {
-                                // println!("There's nothing we can do, {new_spot:?} is a wall!");
-                                return;
-                            }
-                            Thing::Empty => {
-                                continue;
-                            }
-                            Thing::Box => {
-                                new_spots_to_move.insert(new_spot);
-                            }
-                            Thing::LeftBox => {
-                                new_spots_to_move.insert(new_spot);
-                                new_spots_to_move
-                                    .insert(new_spot.unbound_neighbour(Direction::Right));
-                            }
-                            Thing::RightBox => {
-                                new_spots_to_move.insert(new_spot);
-                                new_spots_to_move
-                                    .insert(new_spot.unbound_neighbour(Direction::Left));
-                            }
-                            any => panic!("I got {any:?} but I shouldn't have!"),
+                        Thing::Empty => {
+                            continue;
                         }
-                    }
+                        _ => {
+                            new_spots_to_move.insert(new_spot);
+                        }
+                    },
+                    Direction::Up | Direction::Down => match spot_thing {
+                        Thing::Wall => {
+                            trace!("There's nothing we can do, {new_spot:?} is a wall!");
+                            return;
+                        }
+                        Thing::Empty => {
+                            continue;
+                        }
+                        Thing::Box => {
+                            new_spots_to_move.insert(new_spot);
+                        }
+                        Thing::LeftBox => {
+                            new_spots_to_move.insert(new_spot);
+                            new_spots_to_move.insert(new_spot.unbound_neighbour(Direction::Right));
+                        }
+                        Thing::RightBox => {
+                            new_spots_to_move.insert(new_spot);
+                            new_spots_to_move.insert(new_spot.unbound_neighbour(Direction::Left));
+                        }
+                        any => panic!("I got {any:?} but I shouldn't have!"),
+                    },
                 }
             }
 
@@ -173,7 +188,7 @@ impl Map {
         }
 
         // Move in reverse order, but skip the robot line and the wall line.
-        // println!("{spots_to_move:?}");
+        trace!("{spots_to_move:?}");
         for last_spots_to_move in spots_to_move.iter().rev() {
             for spot in last_spots_to_move {
                 let spot_to_move_to = spot.unbound_neighbour(direction);
@@ -187,6 +202,17 @@ impl Map {
     }
 }
 
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = self
+            .map
+            .iter()
+            .map(|row| row.iter().map(|thing| thing.to_char()).join(""));
+
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
 fn parse_data(file_path: String, bigger: bool) -> (Map, Vec<Direction>) {
     let (map_lines, direction_lines) = read_two_chunks(file_path);
 
@@ -216,40 +242,133 @@ pub fn run(file_path: String, part: i32) -> usize {
     }
 }
 
-fn part_1(file_path: String) -> usize {
-    let (mut map, directions) = parse_data(file_path, false);
-    map.print_to_console();
+fn simulate(map: &mut Map, directions: Vec<Direction>, mut recorder: Option<&mut Recorder>) {
+    for (step, direction) in directions.into_iter().enumerate() {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(step, || map.to_canvas());
+        }
 
-    for direction in directions {
-        // println!("Moving {direction:?}:");
+        trace!("Moving {direction:?}:");
         map.move_robot(direction);
-        // map.print_to_console();
+        trace!("\n{map}");
+    }
+
+    if let Some(recorder) = recorder {
+        recorder.record_always(map.to_canvas());
     }
+}
+
+pub fn render(file_path: String, output_path: String) {
+    let (mut map, directions) = parse_data(file_path, true);
+    simulate(&mut map, directions, None);
+    map.to_canvas().save(output_path, 8);
+}
+
+// Roughly 500 frames, however many moves there are.
+pub fn animate(file_path: String, fps: usize) -> Recorder {
+    let (mut map, directions) = parse_data(file_path, true);
 
-    map.print_to_console();
+    let mut recorder = Recorder::new(fps, directions.len() / 500);
+    simulate(&mut map, directions, Some(&mut recorder));
 
-    map.get_boxes()
-        .into_iter()
-        .map(|point| (point.row * 100) as usize + point.column as usize)
-        .sum()
+    recorder
+}
+
+fn part_1(file_path: String) -> usize {
+    let (mut map, directions) = parse_data(file_path, false);
+    debug!("\n{map}");
+    simulate(&mut map, directions, None);
+    debug!("\n{map}");
+
+    map.get_gps_sum()
 }
 
 fn part_2(file_path: String) -> usize {
     let (mut map, directions) = parse_data(file_path, true);
-    map.print_to_console();
+    debug!("\n{map}");
+    simulate(&mut map, directions, None);
+    debug!("\n{map}");
 
-    for direction in directions {
-        // println!("Moving {direction:?}:");
-        map.move_robot(direction);
-        // map.print_to_console();
+    map.get_gps_sum()
+}
+
+// The warehouse plus the robot's planned moves, which can be stepped through or ignored.
+struct WarehouseSession {
+    file_path: String,
+    map: Map,
+    directions: Vec<Direction>,
+    next_direction: usize,
+}
+
+impl WarehouseSession {
+    fn new(file_path: String, bigger: bool) -> Self {
+        let (map, directions) = parse_data(file_path.clone(), bigger);
+
+        Self {
+            file_path,
+            map,
+            directions,
+            next_direction: 0,
+        }
+    }
+}
+
+impl Session for WarehouseSession {
+    fn get_help(&self) -> Vec<&'static str> {
+        vec![
+            "show - draws the warehouse",
+            "step [n] - makes the next n planned moves (default 1)",
+            "move <moves> - makes these moves instead, e.g. move <^^>",
+            "gps - the sum of the boxes' GPS coordinates",
+            "reset [wide] - starts over, with the wide warehouse if asked",
+        ]
     }
 
-    map.print_to_console();
+    fn handle(&mut self, command: &str, arguments: &[&str]) -> Result<String, String> {
+        match command {
+            "show" => Ok(self.map.to_string()),
+            "step" => {
+                let steps = parse_optional_argument(arguments, 0, "n", 1)?;
+                let end = (self.next_direction + steps).min(self.directions.len());
 
-    map.get_boxes()
-        .into_iter()
-        .map(|point| (point.row * 100) as usize + point.column as usize)
-        .sum()
+                for direction in self.directions[self.next_direction..end].iter() {
+                    self.map.move_robot(*direction);
+                }
+
+                self.next_direction = end;
+
+                Ok(format!(
+                    "{} out of {} moves made.",
+                    self.next_direction,
+                    self.directions.len()
+                ))
+            }
+            "move" => {
+                let moves = arguments.concat();
+
+                if let Some(c) = moves.chars().find(|c| !"^>v<".contains(*c)) {
+                    return Err(format!("{c} isn't a move."));
+                }
+
+                for c in moves.chars() {
+                    self.map.move_robot(Direction::from_char(c));
+                }
+
+                Ok(String::new())
+            }
+            "gps" => Ok(self.map.get_gps_sum().to_string()),
+            "reset" => {
+                *self = Self::new(self.file_path.clone(), arguments.first() == Some(&"wide"));
+                Ok(String::new())
+            }
+            _ => Err(format!("Day 15 doesn't know how to {command}.")),
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn repl(file_path: String) {
+    run_interactive(&mut WarehouseSession::new(file_path, false));
 }
 
 #[cfg(test)]
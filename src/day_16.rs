@@ -1,7 +1,9 @@
 use crate::file_utilities::read_lines;
-use crate::map_utilities::{Direction, Point};
+use crate::map_utilities::{Direction, Point, PointMap, PointSet, DIRECTIONS};
+use crate::render_utilities::{Canvas, GREEN, GREY, RED, WHITE, YELLOW};
+use crate::search_utilities::k_shortest_paths_by_key;
 use std::cmp;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::BinaryHeap;
 
 use itertools::Itertools;
 
@@ -60,7 +62,7 @@ impl Node {
 
 impl PartialOrd for Node {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(other.score.cmp(&self.score))
+        Some(self.cmp(other))
     }
 }
 
@@ -82,15 +84,15 @@ impl Eq for Node {}
 struct Map {
     start: Point,
     end: Point,
-    walls: HashSet<Point>,
+    walls: PointSet,
 }
 
 impl Map {
-    fn new() -> Self {
+    fn new(width: usize, height: usize) -> Self {
         Self {
             start: Point::new(0, 0),
             end: Point::new(0, 0),
-            walls: HashSet::new(),
+            walls: PointSet::new(width, height),
         }
     }
 
@@ -100,7 +102,8 @@ impl Map {
         let mut best_solutions = vec![];
         let mut best_score = usize::MAX;
 
-        let mut best_score_per_point: HashMap<(Point, Direction), usize> = HashMap::new();
+        let mut best_score_per_point: PointMap<[usize; 4]> =
+            PointMap::new(self.walls.width(), self.walls.height());
 
         heap.push(Node::new(self.start, Direction::Right, 0, vec![]));
 
@@ -128,17 +131,17 @@ impl Map {
                 continue;
             }
 
-            let key = (current_node.point, current_node.direction);
+            let best_known_cost = &mut best_score_per_point
+                .get_or_insert_with(current_node.point, || [usize::MAX; 4])
+                [current_node.direction as usize];
 
-            if let Some(best_known_cost) = best_score_per_point.get(&key) {
-                // If we already encountered this node and the cost is better,
-                // we shouldn't keep investigating it.
-                if *best_known_cost < current_node.score {
-                    continue;
-                }
+            // If we already encountered this node and the cost is better,
+            // we shouldn't keep investigating it.
+            if *best_known_cost < current_node.score {
+                continue;
             }
 
-            best_score_per_point.insert(key, current_node.score);
+            *best_known_cost = current_node.score;
 
             heap.push(current_node.move_forward());
 
@@ -174,12 +177,75 @@ impl Map {
 
         best_solutions
     }
+
+    fn get_moves(
+        &self,
+        (point, direction): (Point, Direction),
+    ) -> Vec<((Point, Direction), usize)> {
+        // Every move is a (possibly empty) turn followed by a step forward.
+        DIRECTIONS
+            .into_iter()
+            .map(|new_direction| {
+                let turn_cost = if new_direction == direction {
+                    0
+                } else if new_direction == direction.reverse() {
+                    2000
+                } else {
+                    1000
+                };
+
+                (
+                    (point.unbound_neighbour(new_direction), new_direction),
+                    turn_cost + 1,
+                )
+            })
+            .filter(|((new_point, _), _)| !self.walls.contains(new_point))
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    fn k_best_paths(&self, k: usize) -> Vec<(Vec<Point>, usize)> {
+        // Turning around on a tile is a different state, but not a different tile.
+        k_shortest_paths_by_key(
+            &(self.start, Direction::Right),
+            |node| self.get_moves(*node),
+            |(point, _)| *point == self.end,
+            |(point, _)| *point,
+            k,
+        )
+        .into_iter()
+        .map(|(path, score)| (path.into_iter().map(|(point, _)| point).collect(), score))
+        .collect()
+    }
 }
 
-fn parse_data(file_path: String) -> Map {
-    let mut map = Map::new();
+fn get_canvas(map: &Map, best_paths: &[Node]) -> Canvas {
+    let mut canvas = Canvas::from_points(
+        map.walls.width(),
+        map.walls.height(),
+        map.walls.iter(),
+        GREY,
+        WHITE,
+    );
+
+    canvas.add_points(
+        best_paths
+            .iter()
+            .flat_map(|node| node.history.iter().map(|(point, _)| *point))
+            .unique(),
+        YELLOW,
+    );
+    canvas.add_points([map.start], GREEN);
+    canvas.add_points([map.end], RED);
+
+    canvas
+}
+
+fn parse_lines(lines: Vec<String>) -> Map {
+    let width = lines.first().map_or(0, |line| line.len());
+    let mut map = Map::new(width, lines.len());
 
-    for (row_index, row) in read_lines(file_path).into_iter().enumerate() {
+    for (row_index, row) in lines.into_iter().enumerate() {
         for (column_index, character) in row.chars().enumerate() {
             match character {
                 '.' => continue,
@@ -197,6 +263,10 @@ fn parse_data(file_path: String) -> Map {
     map
 }
 
+fn parse_data(file_path: String) -> Map {
+    parse_lines(read_lines(file_path))
+}
+
 #[allow(dead_code)]
 pub fn run(file_path: String, part: i32) -> usize {
     match part {
@@ -206,6 +276,11 @@ pub fn run(file_path: String, part: i32) -> usize {
     }
 }
 
+pub fn render(file_path: String, output_path: String) {
+    let map = parse_data(file_path);
+    get_canvas(&map, &map.find_paths()).save(output_path, 8);
+}
+
 fn part_1(file_path: String) -> usize {
     let map = parse_data(file_path);
     map.find_paths().first().unwrap().score
@@ -240,4 +315,29 @@ mod tests {
     fn test_part_2(#[case] is_test: bool, #[case] expected: usize) {
         assert_eq!(expected, part_2(get_file_path(is_test, 16, None)));
     }
+
+    #[test]
+    fn test_k_best_paths() {
+        let map = parse_lines(
+            ["######", "#...E#", "#.##.#", "#S...#", "######"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        );
+
+        let paths = map.k_best_paths(2);
+        let scores = paths.iter().map(|(_, score)| *score).collect_vec();
+
+        assert_eq!(vec![1005, 2005], scores);
+        assert_eq!(Point::new(3, 4), paths[0].0[3]);
+        assert_eq!(Point::new(1, 1), paths[1].0[2]);
+
+        // Turning around and going back would make more, but not without visiting a tile twice.
+        let paths = map.k_best_paths(6);
+        assert_eq!(2, paths.len());
+
+        for (path, _) in paths {
+            assert_eq!(path.len(), path.iter().unique().count());
+        }
+    }
 }
@@ -1,6 +1,11 @@
-use itertools::Itertools;
+use crate::file_utilities::read_two_chunks;
+use crate::repl_utilities::{parse_argument, parse_optional_argument, run_interactive, Session};
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
 
-// TODO: real input from file!
+use itertools::Itertools;
 
 #[allow(dead_code)]
 pub fn run(file_path: String, part: i32) -> String {
@@ -37,8 +42,288 @@ impl Operator {
             _ => panic!("Op code {op_code} shouldn't be possible!"),
         }
     }
+
+    fn to_mnemonic(self) -> &'static str {
+        match self {
+            Operator::Adv => "adv",
+            Operator::Bxl => "bxl",
+            Operator::Bst => "bst",
+            Operator::Jnz => "jnz",
+            Operator::Bxc => "bxc",
+            Operator::Out => "out",
+            Operator::Bdv => "bdv",
+            Operator::Cdv => "cdv",
+        }
+    }
+
+    fn from_mnemonic(mnemonic: &str) -> Option<Operator> {
+        (0..8)
+            .map(Operator::from_op_code)
+            .find(|operator| operator.to_mnemonic() == mnemonic)
+    }
+
+    fn takes_combo_operand(self) -> bool {
+        matches!(
+            self,
+            Operator::Adv | Operator::Bst | Operator::Out | Operator::Bdv | Operator::Cdv
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Register {
+    A,
+    B,
+    C,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Operand {
+    Literal(u64),
+    Register(Register),
+    // Combo operand 7 - it shouldn't show up in a valid program.
+    Reserved,
+}
+
+impl Operand {
+    fn from_combo(operand: u64) -> Operand {
+        match operand {
+            0..=3 => Operand::Literal(operand),
+            4 => Operand::Register(Register::A),
+            5 => Operand::Register(Register::B),
+            6 => Operand::Register(Register::C),
+            _ => Operand::Reserved,
+        }
+    }
+}
+
+impl Operand {
+    fn encode(self) -> u64 {
+        match self {
+            Operand::Literal(value) => value,
+            Operand::Register(Register::A) => 4,
+            Operand::Register(Register::B) => 5,
+            Operand::Register(Register::C) => 6,
+            Operand::Reserved => 7,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Literal(value) => write!(f, "{value}"),
+            Operand::Register(register) => write!(f, "{register:?}"),
+            Operand::Reserved => write!(f, "7"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Instruction {
+    operator: Operator,
+    // Combo operands are resolved, literal ones (and bxc's ignored one) are kept as they are.
+    operand: Operand,
+}
+
+impl Instruction {
+    fn decode(op_code: u64, operand: u64) -> Self {
+        let operator = Operator::from_op_code(op_code);
+
+        let operand = if operator.takes_combo_operand() {
+            Operand::from_combo(operand)
+        } else {
+            Operand::Literal(operand)
+        };
+
+        Self { operator, operand }
+    }
+
+    fn encode(&self) -> [u64; 2] {
+        [self.operator as u64, self.operand.encode()]
+    }
+
+    // What the instruction does, in something closer to normal code.
+    fn describe(&self) -> String {
+        let operand = self.operand;
+
+        let description = match self.operator {
+            Operator::Adv => format!("A = A >> {operand}"),
+            Operator::Bxl => format!("B = B ^ {operand}"),
+            Operator::Bst => format!("B = {operand} % 8"),
+            Operator::Jnz => format!("if A != 0, jump to {operand}"),
+            Operator::Bxc => "B = B ^ C".to_string(),
+            Operator::Out => format!("output {operand} % 8"),
+            Operator::Bdv => format!("B = A >> {operand}"),
+            Operator::Cdv => format!("C = A >> {operand}"),
+        };
+
+        if operand == Operand::Reserved {
+            format!("{description} - operand 7 is reserved!")
+        } else {
+            description
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.operator, self.operand) {
+            // The operand is ignored, so only worth showing if it's something unusual.
+            (Operator::Bxc, Operand::Literal(0)) => write!(f, "bxc"),
+            (operator, operand) => write!(f, "{} {operand}", operator.to_mnemonic()),
+        }
+    }
+}
+
+// Every instruction with its address, from the start of the program. A dangling op code at the
+// end (with no operand) isn't an instruction, so it's left out.
+fn decode(program: &[u64]) -> Vec<(usize, Instruction)> {
+    program
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(index, pair)| (index * 2, Instruction::decode(pair[0], pair[1])))
+        .collect()
+}
+
+// One annotated line per instruction, e.g. " 0: bst A      ; B = A % 8".
+pub fn disassemble(program: &[u64]) -> String {
+    let mut lines = decode(program)
+        .into_iter()
+        .map(|(address, instruction)| {
+            format!(
+                "{address:>2}: {:<10} ; {}",
+                instruction.to_string(),
+                instruction.describe()
+            )
+        })
+        .collect_vec();
+
+    if program.len() % 2 == 1 {
+        lines.push(format!(
+            "{:>2}: {:<10} ; op code without an operand!",
+            program.len() - 1,
+            program[program.len() - 1]
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn parse_literal(operand: &str) -> Result<u64, String> {
+    match operand.parse::<u64>() {
+        Ok(value) if value < 8 => Ok(value),
+        _ => Err(format!("{operand} isn't a 3-bit literal.")),
+    }
+}
+
+fn parse_combo(operand: &str) -> Result<Operand, String> {
+    match operand.to_uppercase().as_str() {
+        "A" => Ok(Operand::Register(Register::A)),
+        "B" => Ok(Operand::Register(Register::B)),
+        "C" => Ok(Operand::Register(Register::C)),
+        _ => match parse_literal(operand)? {
+            7 => Err("Combo operand 7 is reserved.".to_string()),
+            4..=6 => Err(format!(
+                "Combo operand {operand} means {}, so write that instead.",
+                Operand::from_combo(operand.parse().unwrap())
+            )),
+            value => Ok(Operand::Literal(value)),
+        },
+    }
+}
+
+// Jumps can go to a label or straight to an address, either way it has to fit in 3 bits and
+// land on an instruction.
+fn parse_jump_target(operand: &str, labels: &HashMap<&str, usize>) -> Result<u64, String> {
+    let target = match labels.get(operand) {
+        Some(address) => *address as u64,
+        None => operand
+            .parse::<u64>()
+            .map_err(|_| format!("There's no label {operand}."))?,
+    };
+
+    if target >= 8 {
+        Err(format!("Jump target {target} doesn't fit in 3 bits."))
+    } else if target % 2 == 1 {
+        Err(format!(
+            "Jump target {target} is odd, so it's not an instruction."
+        ))
+    } else {
+        Ok(target)
+    }
+}
+
+fn parse_instruction(text: &str, labels: &HashMap<&str, usize>) -> Result<Instruction, String> {
+    let words = text.split_whitespace().collect_vec();
+
+    let operator = Operator::from_mnemonic(&words[0].to_lowercase())
+        .ok_or_else(|| format!("{} isn't an instruction.", words[0]))?;
+
+    let operand = match (operator, &words[1..]) {
+        (Operator::Bxc, []) => Operand::Literal(0),
+        (_, []) => return Err(format!("{} needs an operand.", words[0])),
+        (Operator::Jnz, [target]) => Operand::Literal(parse_jump_target(target, labels)?),
+        (Operator::Bxl | Operator::Bxc, [literal]) => Operand::Literal(parse_literal(literal)?),
+        (_, [combo]) => parse_combo(combo)?,
+        _ => return Err(format!("{} only takes one operand.", words[0])),
+    };
+
+    Ok(Instruction { operator, operand })
+}
+
+// Splits a line into its label (if any) and its instruction (if any), without comments.
+// Addresses like the disassembler's " 0:" are skipped, so its listings assemble again.
+fn split_line(line: &str) -> (Option<&str>, &str) {
+    let line = line.split([';', '#']).next().unwrap().trim();
+
+    match line.split_once(':') {
+        Some((label, rest)) if label.trim().parse::<usize>().is_ok() => (None, rest.trim()),
+        Some((label, rest)) => (Some(label.trim()), rest.trim()),
+        None => (None, line),
+    }
+}
+
+// Mnemonics to op codes, one instruction per line, e.g.
+//     loop: bst A
+//           out B ; comments are fine
+//           jnz loop
+pub fn assemble(source: &str) -> Result<Vec<u64>, String> {
+    let lines = source.lines().map(split_line).collect_vec();
+
+    // Labels first, so jumps can go forwards too.
+    let mut labels = HashMap::new();
+    let mut address = 0;
+
+    for (index, (label, instruction)) in lines.iter().enumerate() {
+        if let Some(label) = label {
+            if labels.insert(*label, address).is_some() {
+                return Err(format!("Line {}: {label} is defined twice.", index + 1));
+            }
+        }
+
+        if !instruction.is_empty() {
+            address += 2;
+        }
+    }
+
+    let mut program = vec![];
+
+    for (index, (_, instruction)) in lines.iter().enumerate() {
+        if instruction.is_empty() {
+            continue;
+        }
+
+        let instruction = parse_instruction(instruction, &labels)
+            .map_err(|error| format!("Line {}: {error}", index + 1))?;
+
+        program.extend(instruction.encode());
+    }
+
+    Ok(program)
 }
 
+#[derive(Clone, Debug)]
 struct Computer {
     register_a: u64,
     register_b: u64,
@@ -47,6 +332,10 @@ struct Computer {
     program: Vec<u64>,
     instruction_pointer: usize,
     pub outputs: Vec<u64>,
+
+    steps: usize,
+    // Nothing stops a program from looping forever, so there's always a limit.
+    step_limit: Option<usize>,
 }
 
 impl Computer {
@@ -58,31 +347,76 @@ impl Computer {
             program,
             instruction_pointer: 0,
             outputs: vec![],
+            steps: 0,
+            step_limit: Some(DEFAULT_STEP_LIMIT),
+        }
+    }
+
+    fn get_register(&self, register: Register) -> u64 {
+        match register {
+            Register::A => self.register_a,
+            Register::B => self.register_b,
+            Register::C => self.register_c,
         }
     }
 
-    fn get_operand(&self, operand: u64) -> u64 {
+    fn get_registers(&self) -> [u64; 3] {
+        [self.register_a, self.register_b, self.register_c]
+    }
+
+    // Running off the end halts, and so does landing on a dangling op code without an operand.
+    fn is_halted(&self) -> bool {
+        self.instruction_pointer + 1 >= self.program.len()
+    }
+
+    fn is_at_step_limit(&self) -> bool {
+        self.step_limit.is_some_and(|limit| self.steps >= limit)
+    }
+
+    fn get_instruction(&self) -> Option<Instruction> {
+        if self.is_halted() {
+            return None;
+        }
+
+        Some(Instruction::decode(
+            self.program[self.instruction_pointer],
+            self.program[self.instruction_pointer + 1],
+        ))
+    }
+
+    fn get_operand(&self, operand: u64) -> Result<u64, String> {
         match operand {
-            0 => 0,
-            1 => 1,
-            2 => 2,
-            3 => 3,
-            4 => self.register_a,
-            5 => self.register_b,
-            6 => self.register_c,
-            7 => panic!("7 is reserved!"),
-            _ => panic!("How did you get here?"),
+            0 => Ok(0),
+            1 => Ok(1),
+            2 => Ok(2),
+            3 => Ok(3),
+            4 => Ok(self.register_a),
+            5 => Ok(self.register_b),
+            6 => Ok(self.register_c),
+            _ => Err(format!(
+                "{}: operand {operand} is reserved.",
+                self.instruction_pointer
+            )),
         }
     }
 
-    fn run_instruction(&mut self) {
-        // println!("Running instructions at {}", self.instruction_pointer);
+    // Leaves everything as it was if the instruction can't be run.
+    fn run_instruction(&mut self) -> Result<(), String> {
+        trace!("Running instructions at {}", self.instruction_pointer);
 
         let operator = Operator::from_op_code(self.program[self.instruction_pointer]);
         let literal_operand = self.program[self.instruction_pointer + 1];
-        let combo_operand = self.get_operand(literal_operand);
 
-        // println!("Operator {operator:?}, literal operand {literal_operand}, combo operand {combo_operand}");
+        // Only looked up for the operators that use it, bxl 7 and jnz 7 are fine.
+        let combo_operand = if operator.takes_combo_operand() {
+            self.get_operand(literal_operand)?
+        } else {
+            literal_operand
+        };
+
+        self.steps += 1;
+
+        trace!("Operator {operator:?}, literal operand {literal_operand}, combo operand {combo_operand}");
 
         match operator {
             Operator::Adv => {
@@ -100,7 +434,7 @@ impl Computer {
             Operator::Jnz => {
                 if self.register_a != 0 {
                     self.instruction_pointer = literal_operand as usize;
-                    return;
+                    return Ok(());
                 }
             }
             Operator::Bxc => {
@@ -122,92 +456,969 @@ impl Computer {
         }
 
         self.instruction_pointer += 2;
+        Ok(())
     }
 
     fn divide(&self, operand: u64) -> u64 {
         let numerator = self.register_a;
-        numerator >> operand
+
+        numerator
+            .checked_shr(operand.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0)
+    }
+
+    fn run_to_end(&mut self) -> Result<Vec<u64>, String> {
+        while !self.is_halted() {
+            if self.is_at_step_limit() {
+                warn!(
+                    "Stopped after {} steps, the program might never halt.",
+                    self.steps
+                );
+                break;
+            }
+
+            self.run_instruction()?;
+        }
+
+        Ok(self.outputs.clone())
+    }
+}
+
+const DEFAULT_STEP_LIMIT: usize = 1_000_000;
+
+// The same machine, but with the program decoded once up front, so it can be reset and run with
+// lots of different registers cheaply. There's an instruction for every address, not just the
+// even ones, since nothing stops a program from jumping to an odd one.
+struct CompiledComputer {
+    instructions: Vec<Instruction>,
+    registers: [u64; 3],
+    instruction_pointer: usize,
+    outputs: Vec<u64>,
+    steps: usize,
+    step_limit: Option<usize>,
+}
+
+impl CompiledComputer {
+    fn new(program: &[u64]) -> Self {
+        Self {
+            instructions: program
+                .windows(2)
+                .map(|pair| Instruction::decode(pair[0], pair[1]))
+                .collect(),
+            registers: [0; 3],
+            instruction_pointer: 0,
+            outputs: vec![],
+            steps: 0,
+            step_limit: Some(DEFAULT_STEP_LIMIT),
+        }
+    }
+
+    // Keeps the program and the space for the outputs, everything else starts over.
+    fn reset(&mut self, registers: [u64; 3]) {
+        self.registers = registers;
+        self.instruction_pointer = 0;
+        self.outputs.clear();
+        self.steps = 0;
+    }
+
+    fn is_halted(&self) -> bool {
+        self.instruction_pointer >= self.instructions.len()
+    }
+
+    fn is_at_step_limit(&self) -> bool {
+        self.step_limit.is_some_and(|limit| self.steps >= limit)
+    }
+
+    // Operand 7 is only a problem if the program actually gets to it, so it's checked here.
+    fn get_value(&self, operand: Operand) -> Result<u64, String> {
+        match operand {
+            Operand::Literal(value) => Ok(value),
+            Operand::Register(register) => Ok(self.registers[register as usize]),
+            Operand::Reserved => Err(format!(
+                "{}: operand 7 is reserved.",
+                self.instruction_pointer
+            )),
+        }
+    }
+
+    fn divide(&self, operand: Operand) -> Result<u64, String> {
+        let shift = self.get_value(operand)?;
+
+        Ok(self.registers[Register::A as usize]
+            .checked_shr(shift.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0))
+    }
+
+    // Returns whatever the instruction outputs, and leaves it to the caller to keep it.
+    fn run_instruction(&mut self) -> Result<Option<u64>, String> {
+        let Instruction { operator, operand } = self.instructions[self.instruction_pointer];
+        let mut output = None;
+        let mut jump = None;
+
+        match operator {
+            Operator::Adv => self.registers[Register::A as usize] = self.divide(operand)?,
+            Operator::Bxl => self.registers[Register::B as usize] ^= self.get_value(operand)?,
+            Operator::Bst => self.registers[Register::B as usize] = self.get_value(operand)? % 8,
+            Operator::Jnz => {
+                if self.registers[Register::A as usize] != 0 {
+                    jump = Some(self.get_value(operand)? as usize);
+                }
+            }
+            Operator::Bxc => {
+                self.registers[Register::B as usize] ^= self.registers[Register::C as usize]
+            }
+            Operator::Out => output = Some(self.get_value(operand)? % 8),
+            Operator::Bdv => self.registers[Register::B as usize] = self.divide(operand)?,
+            Operator::Cdv => self.registers[Register::C as usize] = self.divide(operand)?,
+        }
+
+        // Only moved on once the instruction is done, so any error points at the right address.
+        self.steps += 1;
+        self.instruction_pointer = jump.unwrap_or(self.instruction_pointer + 2);
+
+        Ok(output)
+    }
+
+    fn run_to_end(&mut self) -> Result<&[u64], String> {
+        while !self.is_halted() {
+            if self.is_at_step_limit() {
+                warn!(
+                    "Stopped after {} steps, the program might never halt.",
+                    self.steps
+                );
+                break;
+            }
+
+            if let Some(output) = self.run_instruction()? {
+                self.outputs.push(output);
+            }
+        }
+
+        Ok(&self.outputs)
+    }
+
+    // Whether the whole output is exactly this, stopping at the first output that isn't.
+    fn outputs_exactly(&mut self, expected: &[u64]) -> Result<bool, String> {
+        while !self.is_halted() {
+            if self.is_at_step_limit() {
+                return Ok(false);
+            }
+
+            if let Some(output) = self.run_instruction()? {
+                if expected.get(self.outputs.len()) != Some(&output) {
+                    return Ok(false);
+                }
+
+                self.outputs.push(output);
+            }
+        }
+
+        Ok(self.outputs.len() == expected.len())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Watch {
+    Changes(Register),
+    Equals(Register, u64),
+}
+
+impl fmt::Display for Watch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Watch::Changes(register) => write!(f, "{register:?} changes"),
+            Watch::Equals(register, value) => write!(f, "{register:?} == {value}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Stop {
+    Stepped,
+    Halted,
+    Breakpoint(usize),
+    Watch(Watch),
+    StepLimit(usize),
+    Error(String),
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stop::Stepped => write!(f, "Stepped"),
+            Stop::Halted => write!(f, "Halted"),
+            Stop::Breakpoint(address) => write!(f, "Breakpoint at {address}"),
+            Stop::Watch(watch) => write!(f, "Watch: {watch}"),
+            Stop::StepLimit(steps) => write!(f, "Stopped after {steps} steps"),
+            Stop::Error(error) => write!(f, "Error: {error}"),
+        }
     }
+}
+
+// One executed instruction, with the registers (and output, if any) it left behind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TraceEntry {
+    step: usize,
+    address: usize,
+    instruction: Instruction,
+    registers: [u64; 3],
+    output: Option<u64>,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.registers;
+
+        write!(
+            f,
+            "#{} {:>2}: {:<10} A={a} B={b} C={c}",
+            self.step,
+            self.address,
+            self.instruction.to_string()
+        )?;
+
+        if let Some(output) = self.output {
+            write!(f, " -> {output}")?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Debugger {
+    computer: Computer,
+    breakpoints: BTreeSet<usize>,
+    watches: Vec<Watch>,
+    trace: Vec<TraceEntry>,
+}
+
+impl Debugger {
+    fn new(computer: Computer) -> Self {
+        Self {
+            computer,
+            breakpoints: BTreeSet::new(),
+            watches: vec![],
+            trace: vec![],
+        }
+    }
+
+    fn step(&mut self) -> Stop {
+        if self.computer.is_halted() {
+            return Stop::Halted;
+        }
+
+        if self.computer.is_at_step_limit() {
+            return Stop::StepLimit(self.computer.steps);
+        }
+
+        let address = self.computer.instruction_pointer;
+        let instruction = self.computer.get_instruction().unwrap();
+        let registers_before = self.computer.get_registers();
+        let output_count = self.computer.outputs.len();
+
+        if let Err(error) = self.computer.run_instruction() {
+            return Stop::Error(error);
+        }
+
+        self.trace.push(TraceEntry {
+            step: self.computer.steps,
+            address,
+            instruction,
+            registers: self.computer.get_registers(),
+            output: self.computer.outputs.get(output_count).copied(),
+        });
+
+        let triggered = self.watches.iter().find(|watch| match watch {
+            Watch::Changes(register) => {
+                registers_before[*register as usize] != self.computer.get_register(*register)
+            }
+            Watch::Equals(register, value) => self.computer.get_register(*register) == *value,
+        });
 
-    fn run_to_end(&mut self) -> Vec<u64> {
-        while self.instruction_pointer < self.program.len() {
-            self.run_instruction();
+        match triggered {
+            Some(watch) => Stop::Watch(*watch),
+            None if self.computer.is_halted() => Stop::Halted,
+            None => Stop::Stepped,
         }
+    }
+
+    // Runs until something interesting happens. A breakpoint we're already sitting on doesn't
+    // count, or continuing from one would never go anywhere.
+    fn resume(&mut self) -> Stop {
+        loop {
+            match self.step() {
+                Stop::Stepped => {}
+                stop => return stop,
+            }
 
-        self.outputs.clone()
+            let address = self.computer.instruction_pointer;
+
+            if self.breakpoints.contains(&address) && !self.computer.is_halted() {
+                return Stop::Breakpoint(address);
+            }
+        }
     }
 }
 
-const TEST_CASE: (u64, &[u64]) = (729, &[0, 1, 5, 4, 3, 0]);
-const SECOND_TEST_CASE: (u64, &[u64]) = (2024, &[0, 3, 5, 4, 3, 0]);
-const REAL_CASE: (u64, &[u64]) = (66171486, &[2, 4, 1, 6, 7, 5, 4, 6, 1, 4, 5, 5, 0, 3, 3, 0]);
+fn parse_register_line(line: &str) -> u64 {
+    line.split(":")
+        .nth(1)
+        .unwrap()
+        .trim()
+        .parse::<u64>()
+        .unwrap()
+}
+
+fn parse_data(file_path: String) -> Computer {
+    let (register_lines, program_lines) = read_two_chunks(file_path);
+
+    let registers = register_lines
+        .iter()
+        .map(|line| parse_register_line(line))
+        .collect_vec();
+
+    let program = program_lines[0]
+        .strip_prefix("Program:")
+        .unwrap()
+        .split(",")
+        .map(|n| n.trim().parse::<u64>().unwrap())
+        .collect_vec();
+
+    Computer::new(registers[0], registers[1], registers[2], program)
+}
 
 fn part_1(file_path: String) -> String {
-    let case_data = if file_path.contains("test") {
-        TEST_CASE
-    } else {
-        REAL_CASE
-    };
+    let computer = parse_data(file_path);
 
-    let mut computer = Computer::new(case_data.0, 0, 0, case_data.1.to_vec());
+    let mut compiled = CompiledComputer::new(&computer.program);
+    compiled.reset(computer.get_registers());
 
-    let output = computer.run_to_end();
+    match compiled.run_to_end() {
+        Ok(outputs) => outputs.iter().join(","),
+        Err(error) => format!("No output: {error}"),
+    }
+}
+
+// How far A is shifted every time around the program's loop. The search only works for programs
+// that do that with a single adv by a literal, which is all the ones we've seen.
+fn get_shift(program: &[u64]) -> Result<u64, String> {
+    let operands = decode(program)
+        .into_iter()
+        .filter(|(_, instruction)| instruction.operator == Operator::Adv)
+        .map(|(_, instruction)| instruction.operand)
+        .collect_vec();
+
+    match operands.as_slice() {
+        [Operand::Literal(0)] => Err("adv 0 never changes A.".to_string()),
+        [Operand::Literal(shift)] => Ok(*shift),
+        [] => Err("There's no adv, so A never changes.".to_string()),
+        [operand] => Err(format!("A is shifted by {operand}, not by a fixed amount.")),
+        _ => Err("A is shifted more than once.".to_string()),
+    }
+}
+
+// Every output only depends on what's left of A by then, so the last outputs only depend on its
+// highest bits. That means we can find A a few bits at a time, from the top, keeping every
+// candidate whose whole output matches the end of the program so far.
+fn find_quine(computer: &Computer) -> Result<u64, String> {
+    let program = &computer.program;
+    let shift = get_shift(program)?;
+
+    let mut compiled = CompiledComputer::new(program);
+    let mut candidates = vec![0u64];
+
+    for length in 1..=program.len() {
+        let expected = &program[program.len() - length..];
+
+        // Running out of bits isn't the same as there being no answer, so it gets its own error.
+        let starts = candidates
+            .into_iter()
+            .map(|prefix| prefix.checked_mul(1 << shift))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                format!("A needs more than 64 bits to output the last {length} values.")
+            })?;
+
+        candidates = starts
+            .into_iter()
+            .flat_map(|start| (0..1 << shift).map(move |offset| start + offset))
+            // Zero only ever outputs once, and is the same as not having these bits at all.
+            .filter(|a| *a != 0)
+            .map(|a| {
+                compiled.reset([a, computer.register_b, computer.register_c]);
+                Ok(compiled.outputs_exactly(expected)?.then_some(a))
+            })
+            .flatten_ok()
+            .collect::<Result<Vec<_>, String>>()?;
+
+        debug!(
+            "{} candidates output the last {length} values.",
+            candidates.len()
+        );
+    }
 
-    output
+    candidates
         .into_iter()
-        .map(|n| n.to_string())
-        .collect_vec()
+        .min()
+        .ok_or_else(|| "No value of A makes the program output itself.".to_string())
+}
+
+const WORD_SIZE: usize = 64;
+
+// One bit of a register partway through the loop, in terms of the bits the loop started with.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Bit {
+    Constant(bool),
+    // Bit i of A (or B or C) as it was at the start of the loop.
+    Initial(Register, usize),
+    Not(Box<Bit>),
+    Xor(Box<Bit>, Box<Bit>),
+    // Bit offset + amount of the initial A, where the amount is only known as bits (lowest first).
+    Select(usize, Vec<Bit>),
+}
+
+impl Bit {
+    fn initial(register: Register, index: usize) -> Bit {
+        if index < WORD_SIZE {
+            Bit::Initial(register, index)
+        } else {
+            Bit::Constant(false)
+        }
+    }
+
+    fn not(self) -> Bit {
+        match self {
+            Bit::Constant(value) => Bit::Constant(!value),
+            Bit::Not(bit) => *bit,
+            bit => Bit::Not(Box::new(bit)),
+        }
+    }
+
+    fn xor(self, other: Bit) -> Bit {
+        match (self, other) {
+            (Bit::Constant(false), bit) | (bit, Bit::Constant(false)) => bit,
+            (Bit::Constant(true), bit) | (bit, Bit::Constant(true)) => bit.not(),
+            // Keeping the nots outside means they can cancel out.
+            (Bit::Not(first), second) | (second, Bit::Not(first)) => first.xor(second).not(),
+            (first, second) if first == second => Bit::Constant(false),
+            (first, second) => Bit::Xor(Box::new(first), Box::new(second)),
+        }
+    }
+
+    fn select(offset: usize, amount: Vec<Bit>) -> Bit {
+        let constant_amount = amount
+            .iter()
+            .enumerate()
+            .map(|(index, bit)| match bit {
+                Bit::Constant(value) => Some((*value as usize) << index),
+                _ => None,
+            })
+            .sum::<Option<usize>>();
+
+        match constant_amount {
+            Some(amount) => Bit::initial(Register::A, offset + amount),
+            None => Bit::Select(offset, amount),
+        }
+    }
+
+    fn depends_on(&self, register: Register) -> bool {
+        match self {
+            Bit::Constant(_) => false,
+            Bit::Initial(initial, _) => *initial == register,
+            Bit::Not(bit) => bit.depends_on(register),
+            Bit::Xor(first, second) => first.depends_on(register) || second.depends_on(register),
+            Bit::Select(_, amount) => {
+                register == Register::A || amount.iter().any(|bit| bit.depends_on(register))
+            }
+        }
+    }
+
+    fn evaluate(&self, registers: [u64; 3]) -> bool {
+        let get_bit = |register: Register, index: usize| {
+            index < WORD_SIZE && (registers[register as usize] >> index) & 1 == 1
+        };
+
+        match self {
+            Bit::Constant(value) => *value,
+            Bit::Initial(register, index) => get_bit(*register, *index),
+            Bit::Not(bit) => !bit.evaluate(registers),
+            Bit::Xor(first, second) => first.evaluate(registers) ^ second.evaluate(registers),
+            Bit::Select(offset, amount) => {
+                let amount = amount
+                    .iter()
+                    .enumerate()
+                    .map(|(index, bit)| (bit.evaluate(registers) as usize) << index)
+                    .sum::<usize>();
+
+                get_bit(Register::A, offset + amount)
+            }
+        }
+    }
+
+    fn is_atomic(&self) -> bool {
+        !matches!(self, Bit::Xor(_, _))
+    }
+}
+
+impl fmt::Display for Bit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bit::Constant(value) => write!(f, "{}", *value as u8),
+            Bit::Initial(register, index) => {
+                write!(f, "{}{index}", format!("{register:?}").to_lowercase())
+            }
+            Bit::Not(bit) if bit.is_atomic() => write!(f, "!{bit}"),
+            Bit::Not(bit) => write!(f, "!({bit})"),
+            Bit::Xor(first, second) => write!(f, "{first} ^ {second}"),
+            // The amount is written highest bit first, like a number.
+            Bit::Select(offset, amount) => {
+                write!(f, "a[{offset} + ({})]", amount.iter().rev().join(", "))
+            }
+        }
+    }
+}
+
+// What one time around the program's loop does: how far it shifts A, and what it outputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LoopBody {
+    shift: usize,
+    // Every output, lowest bit first.
+    outputs: Vec<Vec<Bit>>,
+}
+
+impl LoopBody {
+    // Only for loops that start from scratch every time, i.e. don't read B or C before setting
+    // them - otherwise the outputs depend on the previous time around.
+    fn evaluate(&self, a: u64) -> Vec<u64> {
+        self.outputs
+            .iter()
+            .map(|bits| {
+                bits.iter()
+                    .enumerate()
+                    .map(|(index, bit)| (bit.evaluate([a, 0, 0]) as u64) << index)
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn is_self_contained(&self) -> bool {
+        self.outputs
+            .iter()
+            .flatten()
+            .all(|bit| !bit.depends_on(Register::B) && !bit.depends_on(Register::C))
+    }
+}
+
+impl fmt::Display for LoopBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "A = A >> {}", self.shift)?;
+
+        for (output, bits) in self.outputs.iter().enumerate() {
+            for (index, bit) in bits.iter().enumerate() {
+                write!(f, "\nout {output}, bit {index} = {bit}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+type Word = Vec<Bit>;
+
+fn get_constant_word(value: u64) -> Word {
+    (0..WORD_SIZE)
+        .map(|index| Bit::Constant((value >> index) & 1 == 1))
+        .collect()
+}
+
+fn get_constant(word: &Word) -> Option<u64> {
+    word.iter()
+        .enumerate()
+        .map(|(index, bit)| match bit {
+            Bit::Constant(value) => Some((*value as u64) << index),
+            _ => None,
+        })
+        .sum()
+}
+
+// Runs the instructions before the final `jnz 0` once, on symbols instead of numbers. A is only
+// ever shifted by constants here, so it's always the initial A shifted by `a_shift`.
+fn execute_symbolically(program: &[u64]) -> Result<LoopBody, String> {
+    let instructions = decode(program);
+
+    let body = match instructions.split_last() {
+        Some(((_, last), body))
+            if *last
+                == Instruction {
+                    operator: Operator::Jnz,
+                    operand: Operand::Literal(0),
+                } =>
+        {
+            body
+        }
+        _ => return Err("The program doesn't end with jnz 0, so it's not a loop.".to_string()),
+    };
+
+    let mut a_shift = 0;
+    let mut b = (0..WORD_SIZE)
+        .map(|index| Bit::initial(Register::B, index))
+        .collect_vec();
+    let mut c = (0..WORD_SIZE)
+        .map(|index| Bit::initial(Register::C, index))
+        .collect_vec();
+    let mut outputs = vec![];
+
+    for (address, instruction) in body.iter() {
+        let combo = match instruction.operand {
+            Operand::Literal(value) => get_constant_word(value),
+            Operand::Register(Register::A) => (0..WORD_SIZE)
+                .map(|index| Bit::initial(Register::A, index + a_shift))
+                .collect(),
+            Operand::Register(Register::B) => b.clone(),
+            Operand::Register(Register::C) => c.clone(),
+            Operand::Reserved => return Err(format!("{address}: operand 7 is reserved.")),
+        };
+
+        // A shifted by the operand. It has to be a constant, or at least fit in 3 bits.
+        let divide = |amount: &Word| -> Result<Word, String> {
+            if let Some(amount) = get_constant(amount) {
+                return Ok((0..WORD_SIZE)
+                    .map(|index| Bit::initial(Register::A, index + a_shift + amount as usize))
+                    .collect());
+            }
+
+            if amount[3..].iter().any(|bit| *bit != Bit::Constant(false)) {
+                return Err(format!(
+                    "{address}: A is shifted by something that might not fit in 3 bits."
+                ));
+            }
+
+            Ok((0..WORD_SIZE)
+                .map(|index| Bit::select(index + a_shift, amount[..3].to_vec()))
+                .collect())
+        };
+
+        match instruction.operator {
+            Operator::Adv => match get_constant(&combo) {
+                Some(amount) => a_shift += amount as usize,
+                None => {
+                    return Err(format!(
+                        "{address}: A is shifted by something that isn't constant."
+                    ))
+                }
+            },
+            Operator::Bxl => {
+                b = b
+                    .into_iter()
+                    .zip(combo)
+                    .map(|(first, second)| first.xor(second))
+                    .collect();
+            }
+            Operator::Bst => {
+                b = combo
+                    .into_iter()
+                    .take(3)
+                    .chain((3..WORD_SIZE).map(|_| Bit::Constant(false)))
+                    .collect();
+            }
+            Operator::Jnz => return Err(format!("{address}: only the last instruction can jump.")),
+            Operator::Bxc => {
+                b = b
+                    .into_iter()
+                    .zip(c.iter().cloned())
+                    .map(|(first, second)| first.xor(second))
+                    .collect();
+            }
+            Operator::Out => outputs.push(combo.into_iter().take(3).collect()),
+            Operator::Bdv => b = divide(&combo)?,
+            Operator::Cdv => c = divide(&combo)?,
+        }
+    }
+
+    Ok(LoopBody {
+        shift: a_shift,
+        outputs,
+    })
+}
+
+// Like find_quine, but checking each time around the loop against its formulas, instead of
+// running the whole program for every candidate.
+fn solve_quine(program: &[u64]) -> Result<u64, String> {
+    let body = execute_symbolically(program)?;
+
+    if !body.is_self_contained() {
+        return Err("The outputs depend on B or C from the previous time around.".to_string());
+    }
+
+    let outputs_per_loop = body.outputs.len();
+
+    if body.shift == 0 || outputs_per_loop == 0 || !program.len().is_multiple_of(outputs_per_loop) {
+        return Err(format!(
+            "Looping with A >> {} and {outputs_per_loop} outputs can't give {} values.",
+            body.shift,
+            program.len()
+        ));
+    }
+
+    let loops = program.len() / outputs_per_loop;
+    let mut candidates = vec![0u64];
+
+    // The last time around A has to shift to 0, and the times before that it can't.
+    for (index, expected) in program.chunks(outputs_per_loop).enumerate().rev() {
+        let starts = candidates
+            .into_iter()
+            .map(|prefix| prefix.checked_mul(1 << body.shift))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| format!("A needs more than 64 bits to go around {loops} times."))?;
+
+        candidates = starts
+            .into_iter()
+            .flat_map(|start| (0..1 << body.shift).map(move |offset| start + offset))
+            .filter(|a| *a != 0 || index == 0 && loops == 1)
+            .filter(|a| body.evaluate(*a) == expected)
+            .collect_vec();
+
+        debug!(
+            "{} candidates left for time {index} around the loop.",
+            candidates.len()
+        );
+    }
+
+    candidates
         .into_iter()
-        .join(",")
+        .min()
+        .ok_or_else(|| "No value of A makes the program output itself.".to_string())
 }
 
 fn part_2(file_path: String) -> String {
-    let is_test = file_path.contains("test");
-    let case_data = if is_test { SECOND_TEST_CASE } else { REAL_CASE };
+    let computer = parse_data(file_path);
+
+    let quine = solve_quine(&computer.program).or_else(|error| {
+        debug!("Can't solve it symbolically ({error}), so searching instead.");
+        find_quine(&computer)
+    });
+
+    match quine {
+        Ok(a) => a.to_string(),
+        Err(error) => format!("No solution: {error}"),
+    }
+}
+
+// Keeps the program around, so it can be run over and over with different registers.
+struct ComputerSession {
+    registers: [u64; 3],
+    program: Vec<u64>,
+    debugger: Option<Debugger>,
+}
+
+impl ComputerSession {
+    fn new(registers: [u64; 3], program: Vec<u64>) -> Self {
+        Self {
+            registers,
+            program,
+            debugger: None,
+        }
+    }
 
-    let needed_output_values = case_data.1.iter().copied().rev().collect_vec();
+    fn get_computer(&self, arguments: &[&str]) -> Result<Computer, String> {
+        let a = parse_optional_argument(arguments, 0, "a", self.registers[0])?;
+
+        Ok(Computer::new(
+            a,
+            self.registers[1],
+            self.registers[2],
+            self.program.clone(),
+        ))
+    }
+
+    fn get_debugger(&mut self) -> Result<&mut Debugger, String> {
+        self.debugger
+            .as_mut()
+            .ok_or_else(|| "Start the debugger first, with debug [a].".to_string())
+    }
+
+    fn get_state(&self) -> String {
+        let Some(debugger) = self.debugger.as_ref() else {
+            return String::new();
+        };
+
+        let computer = &debugger.computer;
+
+        let next = match computer.get_instruction() {
+            Some(instruction) => format!("{:>2}: {instruction}", computer.instruction_pointer),
+            None => "halted".to_string(),
+        };
+
+        let [a, b, c] = computer.get_registers();
+
+        format!(
+            "{next} | A={a} B={b} C={c} | output {}",
+            computer.outputs.iter().join(",")
+        )
+    }
+}
+
+fn parse_register(arguments: &[&str], index: usize) -> Result<Register, String> {
+    match arguments
+        .get(index)
+        .map(|register| register.to_lowercase())
+        .as_deref()
+    {
+        Some("a") => Ok(Register::A),
+        Some("b") => Ok(Register::B),
+        Some("c") => Ok(Register::C),
+        _ => Err("The register should be a, b or c.".to_string()),
+    }
+}
 
-    let mut possible_solutions = vec![0];
+impl Session for ComputerSession {
+    fn get_help(&self) -> Vec<&'static str> {
+        vec![
+            "program - shows the program",
+            "registers - shows the registers the next run starts with",
+            "set <register> <value> - sets register a, b or c",
+            "run [a] - runs the program, with register A overridden if given",
+            "disassemble - shows the program as annotated instructions",
+            "assemble <path> - replaces the program with the one written in the file",
+            "debug [a] - starts debugging the program, with register A overridden if given",
+            "step [n] - runs the next n instructions (default 1)",
+            "continue - runs until a breakpoint, a watch, the step limit or the end",
+            "break <address> - adds (or removes) a breakpoint",
+            "watch <register> [value] - stops when the register changes (or equals the value)",
+            "limit <steps> - stops the debugged program after that many steps in total",
+            "trace [n] - shows the last n instructions that ran (default 10)",
+            "state - shows the next instruction, the registers and the output so far",
+            "quine - finds the lowest A that makes the program output itself",
+            "symbolic - shows each output bit of the loop as a formula over the bits of A, B and C",
+        ]
+    }
 
-    for needed_output_value in needed_output_values.into_iter() {
-        let mut new_possible_solutions = vec![];
+    fn handle(&mut self, command: &str, arguments: &[&str]) -> Result<String, String> {
+        match command {
+            "program" => Ok(self.program.iter().join(",")),
+            "disassemble" => Ok(disassemble(&self.program)),
+            "assemble" => {
+                let path = parse_argument::<String>(arguments, 0, "path")?;
+                let source = fs::read_to_string(&path).map_err(|error| error.to_string())?;
 
-        for possible_solution in possible_solutions {
-            let starting_value = possible_solution * 8;
+                self.program = assemble(&source)?;
+                Ok(self.program.iter().join(","))
+            }
+            "registers" => Ok(format!(
+                "A: {}, B: {}, C: {}",
+                self.registers[0], self.registers[1], self.registers[2]
+            )),
+            "set" => {
+                let register = parse_register(arguments, 0)?;
 
-            for offset in 0..8 {
-                let a = starting_value + offset;
+                self.registers[register as usize] = parse_argument(arguments, 1, "value")?;
+                Ok(String::new())
+            }
+            "run" => {
+                let mut computer = self.get_computer(arguments)?;
+                let output = computer.run_to_end()?.iter().join(",");
 
-                if a == 0 {
-                    // Don't need to bother with an actual 0.
-                    continue;
+                if computer.is_at_step_limit() {
+                    Ok(format!("{output} (stopped after {} steps)", computer.steps))
+                } else {
+                    Ok(output)
                 }
+            }
+            "debug" => {
+                self.debugger = Some(Debugger::new(self.get_computer(arguments)?));
+                Ok(self.get_state())
+            }
+            "step" => {
+                let steps = parse_optional_argument(arguments, 0, "n", 1)?;
+                let debugger = self.get_debugger()?;
+
+                let mut lines = vec![];
 
-                let mut computer = Computer::new(a, 0, 0, case_data.1.to_vec());
-                let output = computer.run_to_end();
+                for _ in 0..steps {
+                    let stop = debugger.step();
 
-                // println!(
-                //     "For input {a} ({a:b}), compare program {:?} to output {output:?}",
-                //     case_data.1.to_vec(),
-                // );
+                    let is_new_entry = !matches!(stop, Stop::Halted | Stop::Error(_));
 
-                if output[0] == needed_output_value {
-                    // println!("{a} ({a:b}) counts as a possible solution!");
-                    new_possible_solutions.push(a);
+                    if let Some(entry) = debugger.trace.last().filter(|_| is_new_entry) {
+                        lines.push(entry.to_string());
+                    }
+
+                    if stop != Stop::Stepped {
+                        lines.push(stop.to_string());
+                        break;
+                    }
                 }
+
+                lines.push(self.get_state());
+                Ok(lines.join("\n"))
             }
-        }
+            "continue" => {
+                let stop = self.get_debugger()?.resume();
+                Ok(format!("{stop}\n{}", self.get_state()))
+            }
+            "break" => {
+                let address = parse_argument(arguments, 0, "address")?;
+                let debugger = self.get_debugger()?;
 
-        possible_solutions = new_possible_solutions;
+                if debugger.breakpoints.remove(&address) {
+                    Ok(format!("Removed the breakpoint at {address}."))
+                } else {
+                    debugger.breakpoints.insert(address);
+                    Ok(format!("Added a breakpoint at {address}."))
+                }
+            }
+            "watch" => {
+                let register = parse_register(arguments, 0)?;
+
+                let watch = if arguments.len() > 1 {
+                    Watch::Equals(register, parse_argument(arguments, 1, "value")?)
+                } else {
+                    Watch::Changes(register)
+                };
+
+                self.get_debugger()?.watches.push(watch);
+                Ok(format!("Watching for {watch}."))
+            }
+            "limit" => {
+                let steps = parse_argument(arguments, 0, "steps")?;
+                self.get_debugger()?.computer.step_limit = Some(steps);
+                Ok(String::new())
+            }
+            "trace" => {
+                let count = parse_optional_argument(arguments, 0, "n", 10)?;
+                let trace = &self.get_debugger()?.trace;
+
+                Ok(trace[trace.len().saturating_sub(count)..].iter().join("\n"))
+            }
+            "quine" => {
+                let computer = self.get_computer(&[])?;
+                find_quine(&computer).map(|a| a.to_string())
+            }
+            "symbolic" => execute_symbolically(&self.program).map(|body| body.to_string()),
+            "state" => {
+                self.get_debugger()?;
+                Ok(self.get_state())
+            }
+            _ => Err(format!("Day 17 doesn't know how to {command}.")),
+        }
     }
+}
 
-    possible_solutions[0].to_string()
+#[allow(dead_code)]
+pub fn repl(file_path: String) {
+    let computer = parse_data(file_path);
+
+    run_interactive(&mut ComputerSession::new(
+        computer.get_registers(),
+        computer.program,
+    ));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::file_utilities::get_file_path;
+    use crate::random_utilities::Random;
     use rstest::rstest;
 
     #[rstest]
@@ -221,6 +1432,409 @@ mod tests {
     #[case(true, "117440")]
     #[case(false, "90938893795561")]
     fn test_part_2(#[case] is_test: bool, #[case] expected: String) {
-        assert_eq!(expected, part_2(get_file_path(is_test, 17, None)));
+        // The example for part 2 is a different program.
+        let suffix = if is_test { Some("_2") } else { None };
+        assert_eq!(expected, part_2(get_file_path(is_test, 17, suffix)));
+    }
+
+    #[test]
+    fn test_session() {
+        let mut session = ComputerSession::new([2024, 0, 0], vec![0, 3, 5, 4, 3, 0]);
+
+        assert_eq!(
+            Ok("0,3,5,4,3,0".to_string()),
+            session.handle("program", &[])
+        );
+        assert_eq!(
+            Ok("0,3,5,4,3,0".to_string()),
+            session.handle("run", &["117440"])
+        );
+
+        assert_eq!(Ok(String::new()), session.handle("set", &["a", "729"]));
+        assert_eq!(
+            Ok("A: 729, B: 0, C: 0".to_string()),
+            session.handle("registers", &[])
+        );
+        assert!(session.handle("set", &["d", "1"]).is_err());
+        assert!(session.handle("jump", &[]).is_err());
+
+        // bxl only ever takes a literal, so its 7 is fine, but out's isn't.
+        let mut session = ComputerSession::new([10, 0, 0], vec![2, 4, 1, 7, 5, 5, 0, 3, 3, 0]);
+        assert_eq!(Ok("5,6".to_string()), session.handle("run", &[]));
+
+        let mut session = ComputerSession::new([10, 0, 0], vec![5, 7]);
+        assert_eq!(
+            Err("0: operand 7 is reserved.".to_string()),
+            session.handle("run", &[])
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let listing = disassemble(&[2, 4, 1, 2, 7, 5, 4, 6, 1, 3, 5, 5, 0, 3, 3, 0]);
+        let lines = listing.lines().collect_vec();
+
+        assert_eq!(8, lines.len());
+        assert_eq!(" 0: bst A      ; B = A % 8", lines[0]);
+        assert_eq!(" 2: bxl 2      ; B = B ^ 2", lines[1]);
+        assert_eq!(" 4: cdv B      ; C = A >> B", lines[2]);
+        assert_eq!(" 6: bxc 6      ; B = B ^ C", lines[3]);
+        assert_eq!(" 8: bxl 3      ; B = B ^ 3", lines[4]);
+        assert_eq!("10: out B      ; output B % 8", lines[5]);
+        assert_eq!("12: adv 3      ; A = A >> 3", lines[6]);
+        assert_eq!("14: jnz 0      ; if A != 0, jump to 0", lines[7]);
+
+        let listing = disassemble(&[0, 7, 4, 0, 5]);
+        let lines = listing.lines().collect_vec();
+
+        assert_eq!(
+            " 0: adv 7      ; A = A >> 7 - operand 7 is reserved!",
+            lines[0]
+        );
+        assert_eq!(" 2: bxc        ; B = B ^ C", lines[1]);
+        assert_eq!(" 4: 5          ; op code without an operand!", lines[2]);
+    }
+
+    #[test]
+    fn test_assemble() {
+        let program = vec![2, 4, 1, 2, 7, 5, 4, 6, 1, 3, 5, 5, 0, 3, 3, 0];
+
+        assert_eq!(Ok(program.clone()), assemble(&disassemble(&program)));
+
+        let source = "
+            # Prints A in octal, lowest digit first.
+            start: bst a
+                   out B ; B is A % 8
+            adv 3
+            jnz start
+        ";
+        assert_eq!(Ok(vec![2, 4, 5, 5, 0, 3, 3, 0]), assemble(source));
+
+        let forward = "jnz end\nbxc\nend: out 1";
+        assert_eq!(Ok(vec![3, 4, 4, 0, 5, 1]), assemble(forward));
+    }
+
+    #[rstest]
+    #[case("adv 7", "Line 1: Combo operand 7 is reserved.")]
+    #[case("out 5", "Line 1: Combo operand 5 means B, so write that instead.")]
+    #[case("bxl 8", "Line 1: 8 isn't a 3-bit literal.")]
+    #[case("jnz 3", "Line 1: Jump target 3 is odd, so it's not an instruction.")]
+    #[case("jnz nowhere", "Line 1: There's no label nowhere.")]
+    #[case(
+        "bxc\nbxc\nbxc\nbxc\nend: bxc\njnz end",
+        "Line 6: Jump target 8 doesn't fit in 3 bits."
+    )]
+    #[case("mul A", "Line 1: mul isn't an instruction.")]
+    #[case("out", "Line 1: out needs an operand.")]
+    #[case("x: out A\nx: out B", "Line 2: x is defined twice.")]
+    fn test_assemble_errors(#[case] source: &str, #[case] expected: &str) {
+        assert_eq!(Err(expected.to_string()), assemble(source));
+    }
+
+    #[test]
+    fn test_debugger() {
+        // Prints A in octal, lowest digit first.
+        let program = assemble("start: bst A\nout B\nadv 3\njnz start").unwrap();
+        let mut debugger = Debugger::new(Computer::new(83, 0, 0, program.clone()));
+
+        assert_eq!(Stop::Stepped, debugger.step());
+        assert_eq!([83, 3, 0], debugger.computer.get_registers());
+
+        debugger.breakpoints.insert(4);
+        assert_eq!(Stop::Breakpoint(4), debugger.resume());
+        assert_eq!(Some(3), debugger.trace[1].output);
+
+        debugger.breakpoints.clear();
+        debugger.watches.push(Watch::Equals(Register::A, 1));
+        assert_eq!(
+            Stop::Watch(Watch::Equals(Register::A, 1)),
+            debugger.resume()
+        );
+
+        debugger.watches.clear();
+        assert_eq!(Stop::Halted, debugger.resume());
+        assert_eq!(vec![3, 2, 1], debugger.computer.outputs);
+        assert_eq!(12, debugger.trace.len());
+        assert_eq!(
+            "#12  6: jnz 0      A=0 B=1 C=0",
+            debugger.trace[11].to_string()
+        );
+
+        // A never reaches 0 here, so only the limit stops it.
+        let forever = assemble("start: out A\njnz start").unwrap();
+        let mut computer = Computer::new(1, 0, 0, forever.clone());
+        computer.step_limit = Some(100);
+        assert_eq!(50, computer.run_to_end().unwrap().len());
+
+        let mut computer = Computer::new(1, 0, 0, forever);
+        computer.step_limit = Some(100);
+        let mut debugger = Debugger::new(computer);
+        debugger.watches.push(Watch::Changes(Register::A));
+        assert_eq!(Stop::StepLimit(100), debugger.resume());
+
+        // bxl only takes a literal, so its 7 is fine.
+        let program = vec![2, 4, 1, 7, 5, 5, 0, 3, 3, 0];
+        let mut debugger = Debugger::new(Computer::new(10, 0, 0, program));
+        assert_eq!(Stop::Halted, debugger.resume());
+        assert_eq!(vec![5, 6], debugger.computer.outputs);
+
+        // out's isn't, and it stops there without running anything.
+        let mut debugger = Debugger::new(Computer::new(10, 0, 0, vec![2, 4, 5, 7]));
+        assert_eq!(Stop::Stepped, debugger.step());
+        assert_eq!(
+            Stop::Error("2: operand 7 is reserved.".to_string()),
+            debugger.step()
+        );
+        assert_eq!(1, debugger.trace.len());
+        assert_eq!(1, debugger.computer.steps);
+
+        // Shifting by more than there is leaves nothing, rather than overflowing.
+        let mut debugger = Debugger::new(Computer::new(1, 100, 5, vec![7, 5]));
+        assert_eq!(Stop::Halted, debugger.resume());
+        assert_eq!(0, debugger.computer.register_c);
+    }
+
+    fn find_quine_by_brute_force(program: &[u64], limit: u64) -> Option<u64> {
+        (1..limit).find(|a| {
+            Computer::new(*a, 0, 0, program.to_vec()).run_to_end() == Ok(program.to_vec())
+        })
+    }
+
+    #[test]
+    fn test_find_quine() {
+        let octal = vec![0, 3, 5, 4, 3, 0];
+        assert_eq!(Ok(117440), find_quine(&Computer::new(0, 0, 0, octal)));
+
+        // Two bits at a time, so neighbouring outputs share a bit.
+        let quaternary = assemble("start: adv 2\nbst A\nout B\njnz start").unwrap();
+        let expected = find_quine_by_brute_force(&quaternary, 1 << 16);
+
+        assert!(expected.is_some());
+        assert_eq!(
+            expected.ok_or(String::new()),
+            find_quine(&Computer::new(0, 0, 0, quaternary))
+        );
+
+        // One bit at a time, and then 0 can't be followed by 1.
+        let binary = assemble("start: adv 1\nbst A\nout B\njnz start").unwrap();
+
+        assert_eq!(None, find_quine_by_brute_force(&binary, 1 << 12));
+        assert_eq!(
+            Err("No value of A makes the program output itself.".to_string()),
+            find_quine(&Computer::new(0, 0, 0, binary))
+        );
+
+        let by_register = assemble("start: adv B\nout A\njnz start").unwrap();
+        assert!(find_quine(&Computer::new(0, 0, 0, by_register)).is_err());
+
+        let no_shift = assemble("out A").unwrap();
+        assert!(find_quine(&Computer::new(0, 0, 0, no_shift)).is_err());
+
+        // A quine, but only with 66 bits of A.
+        let too_long = get_too_long_program();
+        assert_eq!(
+            Err("A needs more than 64 bits to output the last 22 values.".to_string()),
+            find_quine(&Computer::new(0, 0, 0, too_long))
+        );
+    }
+
+    // Outputs A 3 bits at a time with 3 flipped, so there's always a quine, but the padding makes
+    // the program 22 values long, and A would need 66 bits.
+    fn get_too_long_program() -> Vec<u64> {
+        let padding = "bxl 0\n".repeat(6);
+        assemble(&format!(
+            "start: bst A\nbxl 3\n{padding}out B\nadv 3\njnz start"
+        ))
+        .unwrap()
+    }
+
+    // Shaped like the real inputs: mixes the lowest 3 bits of A with some higher ones, outputs
+    // that and moves on to the next 3 bits.
+    fn get_mixing_program() -> Vec<u64> {
+        assemble("start: bst A\nbxl 2\ncdv B\nbxc\nbxl 3\nout B\nadv 3\njnz start").unwrap()
+    }
+
+    #[rstest]
+    #[case(get_mixing_program())]
+    #[case(vec![0, 3, 5, 4, 3, 0])]
+    #[case(assemble("start: adv 2\nbst A\nout B\njnz start").unwrap())]
+    #[case(assemble("start: bst A\nbxl 3\ncdv B\nbxc\nout B\nout C\nadv 3\njnz start").unwrap())]
+    fn test_execute_symbolically(#[case] program: Vec<u64>) {
+        let body = execute_symbolically(&program).unwrap();
+        let outputs = body.outputs.len();
+
+        assert!(body.is_self_contained());
+
+        for a in Random::new(1).take(100) {
+            let mut computer = Computer::new(a, 0, 0, program.clone());
+            let expected = computer.run_to_end().unwrap();
+
+            assert_eq!(expected[..outputs], body.evaluate(a));
+        }
+    }
+
+    #[test]
+    fn test_describe_symbolically() {
+        let program = assemble("start: bst A\nbxl 5\ncdv B\nbxc\nout B\nadv 3\njnz start").unwrap();
+        let body = execute_symbolically(&program).unwrap().to_string();
+        let lines = body.lines().collect_vec();
+
+        assert_eq!(4, lines.len());
+        assert_eq!("A = A >> 3", lines[0]);
+        assert_eq!("out 0, bit 0 = !(a0 ^ a[0 + (!a2, a1, !a0)])", lines[1]);
+        assert_eq!("out 0, bit 1 = a1 ^ a[1 + (!a2, a1, !a0)]", lines[2]);
+
+        assert!(execute_symbolically(&[5, 4]).is_err());
+        assert!(execute_symbolically(&assemble("start: adv A\njnz start").unwrap()).is_err());
+        assert!(execute_symbolically(&assemble("start: cdv A\njnz start").unwrap()).is_err());
+
+        // B comes from the previous time around, so each time isn't on its own.
+        let carried =
+            execute_symbolically(&assemble("start: bxc\nout B\nadv 3\njnz start").unwrap());
+        assert!(!carried.unwrap().is_self_contained());
+    }
+
+    #[test]
+    fn test_solve_quine() {
+        assert_eq!(Ok(117440), solve_quine(&[0, 3, 5, 4, 3, 0]));
+
+        let quaternary = assemble("start: adv 2\nbst A\nout B\njnz start").unwrap();
+        assert_eq!(
+            find_quine(&Computer::new(0, 0, 0, quaternary.clone())),
+            solve_quine(&quaternary)
+        );
+
+        let binary = assemble("start: adv 1\nbst A\nout B\njnz start").unwrap();
+        assert_eq!(
+            Err("No value of A makes the program output itself.".to_string()),
+            solve_quine(&binary)
+        );
+
+        let mixing = get_mixing_program();
+        assert_eq!(Ok(37221263785460), solve_quine(&mixing));
+        assert_eq!(
+            Ok(mixing.clone()),
+            Computer::new(37221263785460, 0, 0, mixing).run_to_end()
+        );
+
+        let carried = assemble("start: bxc\nout B\nadv 3\njnz start").unwrap();
+        assert!(solve_quine(&carried).is_err());
+
+        assert_eq!(
+            Err("A needs more than 64 bits to go around 22 times.".to_string()),
+            solve_quine(&get_too_long_program())
+        );
+    }
+
+    #[rstest]
+    #[case(get_mixing_program())]
+    #[case(vec![0, 1, 5, 4, 3, 0])]
+    #[case(assemble("start: bst A\nbxl 3\ncdv B\nbxc\nout B\nout C\nadv 3\njnz start").unwrap())]
+    // Jumps to 1, where the operands are read as op codes: bxl 3 and then bxl 0.
+    #[case(vec![5, 1, 3, 1, 0, 4])]
+    fn test_compiled_computer(#[case] program: Vec<u64>) {
+        let mut compiled = CompiledComputer::new(&program);
+        compiled.step_limit = Some(1000);
+
+        for a in Random::new(1).take(100) {
+            let mut computer = Computer::new(a, 0, 0, program.clone());
+            computer.step_limit = Some(1000);
+            let expected = computer.run_to_end().unwrap();
+
+            compiled.reset([a, 0, 0]);
+            assert_eq!(Ok(expected.as_slice()), compiled.run_to_end());
+
+            compiled.reset([a, 0, 0]);
+            assert_eq!(Ok(true), compiled.outputs_exactly(&expected));
+
+            compiled.reset([a, 0, 0]);
+            let longer = [expected.clone(), vec![0]].concat();
+            assert_eq!(Ok(false), compiled.outputs_exactly(&longer));
+        }
+    }
+
+    #[test]
+    fn test_outputs_exactly() {
+        let mut compiled = CompiledComputer::new(&[0, 3, 5, 4, 3, 0]);
+
+        compiled.reset([117440, 0, 0]);
+        assert_eq!(Ok(true), compiled.outputs_exactly(&[0, 3, 5, 4, 3, 0]));
+
+        // The first output is already wrong, so it stops there.
+        compiled.reset([117441, 0, 0]);
+        assert_eq!(Ok(false), compiled.outputs_exactly(&[1, 3, 5, 4, 3, 0]));
+        assert_eq!(2, compiled.steps);
+        assert!(compiled.outputs.is_empty());
+
+        compiled.reset([117440, 0, 0]);
+        assert_eq!(Ok(false), compiled.outputs_exactly(&[0, 3, 5]));
+    }
+
+    #[test]
+    fn test_reserved_operand() {
+        // adv 3, out 7, jnz 0
+        let program = vec![0, 3, 5, 7, 3, 0];
+
+        let mut compiled = CompiledComputer::new(&program);
+        compiled.reset([1, 0, 0]);
+        assert_eq!(
+            Err("2: operand 7 is reserved.".to_string()),
+            compiled.run_to_end()
+        );
+
+        assert_eq!(
+            Err("2: operand 7 is reserved.".to_string()),
+            find_quine(&Computer::new(0, 0, 0, program))
+        );
+
+        // Only a problem if it's actually reached, and here the jump skips it.
+        let mut compiled = CompiledComputer::new(&[3, 4, 5, 7]);
+        compiled.reset([4, 0, 0]);
+        assert_eq!(Ok([].as_slice()), compiled.run_to_end());
+    }
+
+    // cargo test --release bench_computers -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_computers() {
+        use std::time::Instant;
+
+        let program = get_mixing_program();
+        let candidates = Random::new(1).take(200_000).collect_vec();
+
+        let start = Instant::now();
+        let mut found = 0;
+        for a in candidates.iter() {
+            let mut computer = Computer::new(*a, 0, 0, program.clone());
+            found += (computer.run_to_end().unwrap() == program) as usize;
+        }
+        println!(
+            "Computer:                          {:?} ({found})",
+            start.elapsed()
+        );
+
+        let mut compiled = CompiledComputer::new(&program);
+
+        let start = Instant::now();
+        let mut found = 0;
+        for a in candidates.iter() {
+            compiled.reset([*a, 0, 0]);
+            found += (compiled.run_to_end().unwrap() == program) as usize;
+        }
+        println!(
+            "CompiledComputer:                  {:?} ({found})",
+            start.elapsed()
+        );
+
+        let start = Instant::now();
+        let mut found = 0;
+        for a in candidates.iter() {
+            compiled.reset([*a, 0, 0]);
+            found += compiled.outputs_exactly(&program).unwrap() as usize;
+        }
+        println!(
+            "CompiledComputer, stopping early:  {:?} ({found})",
+            start.elapsed()
+        );
     }
 }
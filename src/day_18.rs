@@ -1,22 +1,27 @@
 use crate::file_utilities::read_lines;
-use crate::map_utilities::{Point, DIRECTIONS};
+use crate::map_utilities::{Point, PointSet, DIRECTIONS};
+use crate::repl_utilities::{parse_argument, run_interactive, Session};
+use crate::search_utilities::k_shortest_paths;
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 
 use itertools::Itertools;
 
 struct Map {
     size: usize,
-    walls: HashSet<Point>,
+    walls: PointSet,
     start: Point,
     end: Point,
 }
 
 impl Map {
-    fn new(size: usize, walls: HashSet<Point>) -> Self {
+    fn new<I>(size: usize, walls: I) -> Self
+    where
+        I: IntoIterator<Item = Point>,
+    {
         Self {
             size,
-            walls,
+            walls: PointSet::from_points(size + 1, size + 1, walls),
             start: Point::new(0, 0),
             end: Point::new((size - 1) as isize, (size - 1) as isize),
         }
@@ -26,7 +31,7 @@ impl Map {
         let mut queue = VecDeque::new();
         queue.push_back((self.start, 0));
 
-        let mut visited = HashSet::new();
+        let mut visited = PointSet::new(self.size + 1, self.size + 1);
 
         while let Some((current_point, current_length)) = queue.pop_front() {
             if visited.contains(&current_point) {
@@ -55,6 +60,39 @@ impl Map {
 
         0
     }
+
+    fn get_picture(&self) -> String {
+        (0..=self.size as isize)
+            .map(|row| {
+                (0..=self.size as isize)
+                    .map(|column| {
+                        if self.walls.contains(&Point::new(row, column)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+
+    #[allow(dead_code)]
+    fn k_shortest_paths(&self, k: usize) -> Vec<(Vec<Point>, usize)> {
+        k_shortest_paths(
+            &self.start,
+            |point| {
+                DIRECTIONS
+                    .into_iter()
+                    .filter_map(|direction| point.neighbour(direction, self.size + 1))
+                    .filter(|neighbour| !self.walls.contains(neighbour))
+                    .map(|neighbour| (neighbour, 1))
+                    .collect_vec()
+            },
+            |point| *point == self.end,
+            k,
+        )
+    }
 }
 
 fn parse_line_to_point(line: String) -> Point {
@@ -90,7 +128,7 @@ fn part_1(file_path: String) -> (usize, usize) {
 
     let bytes = parse_data(file_path);
 
-    let map = Map::new(map_size, bytes.into_iter().take(bytes_to_take).collect());
+    let map = Map::new(map_size, bytes.into_iter().take(bytes_to_take));
 
     (map.find_shortest_path(), 0)
 }
@@ -103,12 +141,9 @@ fn part_2(file_path: String) -> (usize, usize) {
     let bytes = parse_data(file_path);
 
     for bytes_to_take in bytes_to_start_from..bytes.len() {
-        // println!("Trying out {bytes_to_take} bytes out of {}.", bytes.len());
+        debug!("Trying out {bytes_to_take} bytes out of {}.", bytes.len());
 
-        let map = Map::new(
-            map_size,
-            bytes.iter().copied().take(bytes_to_take).collect(),
-        );
+        let map = Map::new(map_size, bytes.iter().copied().take(bytes_to_take));
 
         let shortest_path = map.find_shortest_path();
 
@@ -121,6 +156,61 @@ fn part_2(file_path: String) -> (usize, usize) {
     (0, 0)
 }
 
+struct MemorySession {
+    map_size: usize,
+    bytes: Vec<Point>,
+}
+
+impl MemorySession {
+    fn get_map(&self, arguments: &[&str]) -> Result<Map, String> {
+        let bytes_to_take = parse_argument(arguments, 0, "bytes")?;
+
+        Ok(Map::new(
+            self.map_size,
+            self.bytes.iter().copied().take(bytes_to_take),
+        ))
+    }
+}
+
+impl Session for MemorySession {
+    fn get_help(&self) -> Vec<&'static str> {
+        vec![
+            "count - shows how many bytes are falling",
+            "byte <n> - where the nth byte (from 0) falls, as x,y",
+            "show <bytes> - draws the memory after that many bytes fell",
+            "path <bytes> - the shortest path after that many bytes fell",
+        ]
+    }
+
+    fn handle(&mut self, command: &str, arguments: &[&str]) -> Result<String, String> {
+        match command {
+            "count" => Ok(self.bytes.len().to_string()),
+            "byte" => {
+                let index = parse_argument::<usize>(arguments, 0, "n")?;
+
+                match self.bytes.get(index) {
+                    Some(byte) => Ok(format!("{},{}", byte.column, byte.row)),
+                    None => Err(format!("There are only {} bytes.", self.bytes.len())),
+                }
+            }
+            "show" => Ok(self.get_map(arguments)?.get_picture()),
+            "path" => match self.get_map(arguments)?.find_shortest_path() {
+                0 => Ok("There's no way out!".to_string()),
+                length => Ok(length.to_string()),
+            },
+            _ => Err(format!("Day 18 doesn't know how to {command}.")),
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn repl(file_path: String) {
+    let map_size = if file_path.contains("test") { 6 } else { 70 };
+    let bytes = parse_data(file_path);
+
+    run_interactive(&mut MemorySession { map_size, bytes });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,4 +230,22 @@ mod tests {
     fn test_part_2(#[case] is_test: bool, #[case] expected: (usize, usize)) {
         assert_eq!(expected, part_2(get_file_path(is_test, 18, None)));
     }
+
+    #[test]
+    fn test_k_shortest_paths() {
+        let map = Map::new(3, []);
+        let paths = map.k_shortest_paths(7);
+
+        // Six ways to get to (2, 2) in 4 steps, and then the detours start.
+        let lengths = paths.iter().map(|(_, length)| *length).collect_vec();
+        assert_eq!(vec![4, 4, 4, 4, 4, 4, 6], lengths);
+
+        let map = Map::new(3, [Point::new(1, 1)]);
+        let lengths = map
+            .k_shortest_paths(3)
+            .into_iter()
+            .map(|(_, length)| length)
+            .collect_vec();
+        assert_eq!(vec![4, 4, 6], lengths);
+    }
 }
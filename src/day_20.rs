@@ -1,5 +1,6 @@
 use crate::file_utilities::read_lines;
-use crate::map_utilities::{Point, DIRECTIONS};
+use crate::map_utilities::{Grid, Point, DIRECTIONS};
+use crate::render_utilities::{Canvas, BLUE, GREY, RED, WHITE};
 
 fn parse_line_to_chars(line: String) -> Vec<char> {
     line.chars().collect::<Vec<char>>()
@@ -77,7 +78,7 @@ fn get_all_tunnels(path: &[Point], time_limit: isize) -> Vec<(Point, Point, usiz
             }
 
             if cost_at_new_point > cost_at_point + distance_travelled as usize {
-                // println!("Jumping from {point_in_path:?} to {new_point:?} gives {cost_at_point} -> {cost_at_new_point}");
+                trace!("Jumping from {point_in_path:?} to {new_point:?} gives {cost_at_point} -> {cost_at_new_point}");
                 result.push((
                     *point_in_path,
                     *new_point,
@@ -90,6 +91,37 @@ fn get_all_tunnels(path: &[Point], time_limit: isize) -> Vec<(Point, Point, usiz
     result
 }
 
+// The track in blue, and every cheat that saves at least `limit` as a red shortcut.
+fn get_canvas(
+    map: &[Vec<char>],
+    path: &[Point],
+    tunnels: &[(Point, Point, usize)],
+    limit: usize,
+) -> Canvas {
+    let grid = Grid::from_rows(map.to_vec());
+    let mut canvas = Canvas::from_grid(&grid, |c| if *c == '#' { GREY } else { WHITE });
+
+    canvas.add_path(path, BLUE);
+
+    for (from, to, _) in tunnels.iter().filter(|(_, _, cut)| *cut >= limit) {
+        canvas.add_path(&[*from, *to], RED);
+    }
+
+    canvas
+}
+
+pub fn render(file_path: String, output_path: String) {
+    let is_test = file_path.contains("test");
+    let limit = if is_test { 50 } else { 100 };
+
+    let map = parse_data(file_path);
+
+    let path = fine_path(&map);
+    let all_tunnels = get_all_tunnels(&path, 20);
+
+    get_canvas(&map, &path, &all_tunnels, limit).save(output_path, 8);
+}
+
 fn part_1(file_path: String) -> u64 {
     let is_test = file_path.contains("test");
     let limit = if is_test { 0 } else { 100 };
@@ -100,10 +100,10 @@ fn recursive_run(
     depth: usize,
     cache: &mut HashMap<(char, char, usize), usize>,
 ) -> usize {
-    // println!("Getting paths from {from} to {to} at depth {depth}");
+    trace!("Getting paths from {from} to {to} at depth {depth}");
 
     if let Some(result) = cache.get(&(from, to, depth)) {
-        // println!("Found cache of {result}");
+        trace!("Found cache of {result}");
         return *result;
     }
 
@@ -122,7 +122,7 @@ fn recursive_run(
                 .chain(perm)
                 .chain(iter::once('A'))
                 .collect_vec();
-            // println!("Investigating new permutation {}", new_perm.iter().join(""));
+            trace!("Investigating new permutation {}", new_perm.iter().join(""));
 
             new_perm
                 .into_iter()
@@ -143,7 +143,7 @@ fn run_for_robots(sequences: &[&str], robots: usize) -> usize {
     let mut cache = HashMap::new();
 
     for sequence in sequences {
-        // println!("Handling sequence {sequence:?}");
+        debug!("Handling sequence {sequence:?}");
 
         let numeric_part = sequence[0..sequence.len() - 1].parse::<usize>().unwrap();
 
@@ -155,7 +155,7 @@ fn run_for_robots(sequences: &[&str], robots: usize) -> usize {
             .map(|(from, to)| recursive_run(NUMERICAL_PAD, from, to, robots, &mut cache))
             .sum();
 
-        // println!("{sequence}: length {shortest_sequence}, numeric {numeric_part}");
+        debug!("{sequence}: length {shortest_sequence}, numeric {numeric_part}");
         result += shortest_sequence * numeric_part;
     }
 
@@ -59,8 +59,8 @@ fn part_1(file_path: String) -> u64 {
 
     let mut numbers = data.clone();
 
-    for _evolution in 0..2000 {
-        // println!("Evolving {_evolution} out of 2000");
+    for evolution in 0..2000 {
+        trace!("Evolving {evolution} out of 2000");
         numbers = numbers.into_iter().map(evolve).collect_vec();
     }
 
@@ -97,7 +97,7 @@ fn part_2(file_path: String) -> u64 {
                 diffs[index - 2],
                 diffs[index - 1],
             );
-            // println!("At index {index} I have price {price} with tuple {tuple:?}");
+            trace!("At index {index} I have price {price} with tuple {tuple:?}");
             monkey_hashset.entry(tuple).or_insert(price);
         }
 
@@ -1,8 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::HashSet;
 
 use itertools::Itertools;
 
 use crate::file_utilities::read_lines;
+use crate::graph_utilities::Graph;
 
 // TODO: Have part 1 use part 2 by going over clusters that are >= 3 and summing over n-choose-3.
 
@@ -14,35 +16,15 @@ fn parse_edge_line(line: String) -> (String, String) {
         .unwrap()
 }
 
-fn parse_data(file_path: String) -> (Vec<String>, HashMap<String, HashSet<String>>) {
-    let input_edges = read_lines(file_path)
-        .into_iter()
-        .map(parse_edge_line)
-        .collect_vec();
-
-    let nodes = input_edges
-        .iter()
-        .flat_map(|(source, target)| vec![source.clone(), target.clone()])
-        .unique()
-        .collect_vec();
+// Computer names are interned as they're read, so the solvers only ever deal with ids.
+fn parse_data(file_path: String) -> Graph<String> {
+    let mut lan = Graph::new_undirected();
 
-    let mut edges = nodes
-        .iter()
-        .map(|node| (node.clone(), HashSet::new()))
-        .collect::<HashMap<String, HashSet<String>>>();
-
-    for (node_1, node_2) in input_edges.iter() {
-        edges
-            .get_mut(&node_1.clone())
-            .unwrap()
-            .insert(node_2.clone());
-        edges
-            .get_mut(&node_2.clone())
-            .unwrap()
-            .insert(node_1.clone());
+    for (node_1, node_2) in read_lines(file_path).into_iter().map(parse_edge_line) {
+        lan.add_edge(node_1, node_2);
     }
 
-    (nodes, edges)
+    lan
 }
 
 #[allow(dead_code)]
@@ -55,104 +37,126 @@ pub fn run(file_path: String, part: i32) -> String {
 }
 
 fn part_1(file_path: String) -> String {
-    let (nodes, edges) = parse_data(file_path);
-
-    let mut result: HashSet<(String, String, String)> = HashSet::new();
-
-    for node_1 in nodes.iter() {
-        if !node_1.starts_with("t") {
-            continue;
-        }
-
-        for node_2 in nodes.iter() {
-            if !edges[node_1].contains(node_2) {
-                continue;
-            }
+    let lan = parse_data(file_path);
 
-            for node_3 in nodes.iter() {
-                if node_3 == node_1 || node_3 == node_2 {
-                    continue;
-                }
+    let mut result: HashSet<[usize; 3]> = HashSet::new();
 
-                if !edges[node_1].contains(node_3) {
+    for node_1 in lan.node_ids().filter(|id| lan.node(*id).starts_with("t")) {
+        for node_2 in lan.neighbours(node_1).iter().copied() {
+            for node_3 in lan.neighbours(node_2).iter().copied() {
+                if node_3 == node_1 || !lan.contains_edge(node_1, node_3) {
                     continue;
                 }
 
-                if !edges[node_2].contains(node_3) {
-                    continue;
-                }
+                let mut triangle = [node_1, node_2, node_3];
+                triangle.sort();
 
-                let new = [node_1.clone(), node_2.clone(), node_3.clone()];
+                result.insert(triangle);
+            }
+        }
+    }
 
-                if new
-                    .iter()
-                    .permutations(3)
-                    .any(|p| result.contains(&p.iter().copied().cloned().collect_tuple().unwrap()))
-                {
-                    continue;
-                }
+    result.len().to_string()
+}
 
-                result.insert(new.into_iter().collect_tuple().unwrap());
+// Both sides have to be sorted.
+fn intersect(first: &[usize], second: &[usize]) -> Vec<usize> {
+    let mut result = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    while i < first.len() && j < second.len() {
+        match first[i].cmp(&second[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                result.push(first[i]);
+                i += 1;
+                j += 1;
             }
         }
     }
 
-    // println!("{result:?}");
-    result.into_iter().unique().count().to_string()
+    result
 }
 
+// Same as the textbook version, with every set as a sorted list of ids.
 fn bron_kerbosch(
-    r: &mut HashSet<String>,
-    p: &mut HashSet<String>,
-    x: &mut HashSet<String>,
-    edges: &HashMap<String, HashSet<String>>,
-    maximal_clique: &mut Vec<HashSet<String>>,
+    r: &mut Vec<usize>,
+    p: &mut Vec<usize>,
+    x: &mut Vec<usize>,
+    neighbours: &[Vec<usize>],
+    maximal_clique: &mut Vec<Vec<usize>>,
 ) {
     if p.is_empty() && x.is_empty() {
         maximal_clique.push(r.clone());
         return;
     }
 
-    let all_vertices_in_p: Vec<String> = p.iter().cloned().collect_vec();
-
-    for v in all_vertices_in_p.iter() {
-        let v_set: HashSet<String> = vec![v.clone()].into_iter().collect();
-        let neighbours = edges[v].clone();
+    for v in p.clone() {
+        let mut new_p = intersect(p, &neighbours[v]);
+        let mut new_x = intersect(x, &neighbours[v]);
 
-        let mut new_r: HashSet<String> = r.union(&v_set).cloned().collect(); // Don't format!
-        let mut new_p: HashSet<String> = p.intersection(&neighbours).cloned().collect(); //
-        let mut new_x: HashSet<String> = x.intersection(&neighbours).cloned().collect(); //
+        r.push(v);
+        bron_kerbosch(r, &mut new_p, &mut new_x, neighbours, maximal_clique);
+        r.pop();
 
-        bron_kerbosch(&mut new_r, &mut new_p, &mut new_x, edges, maximal_clique);
+        p.retain(|node| *node != v);
 
-        p.remove(&v.clone());
-        x.insert(v.clone());
+        let position = x.partition_point(|node| *node < v);
+        x.insert(position, v);
     }
 }
 
-fn part_2(file_path: String) -> String {
-    let (nodes, edges) = parse_data(file_path);
+fn find_largest_clique(lan: &Graph<String>) -> Vec<String> {
+    let neighbours = lan
+        .node_ids()
+        .map(|id| lan.neighbours(id).iter().copied().sorted().collect_vec())
+        .collect_vec();
 
     // Solve the clique problem!
-    let mut p: HashSet<String> = nodes.iter().cloned().collect();
+    let mut p = lan.node_ids().collect_vec();
 
-    let mut r = HashSet::new();
-    let mut x = HashSet::new();
+    let mut r = vec![];
+    let mut x = vec![];
 
     let mut result = vec![];
 
-    bron_kerbosch(&mut r, &mut p, &mut x, &edges, &mut result);
+    bron_kerbosch(&mut r, &mut p, &mut x, &neighbours, &mut result);
+
+    result
+        .into_iter()
+        .max_by_key(|clique| clique.len())
+        .map(|clique| {
+            clique
+                .into_iter()
+                .map(|id| lan.node(id).clone())
+                .sorted()
+                .collect_vec()
+        })
+        .unwrap_or_default()
+}
 
-    // TODO: Is there a proper way to have max_by_key but for an iterator but without sorting?
-    let max_length = result.iter().map(|r| r.len()).max().unwrap();
+// The whole LAN, with the LAN party as its own cluster.
+pub fn export_graph(file_path: String, output_path: String) {
+    let mut lan = parse_data(file_path);
 
-    for clique in result {
-        if clique.len() == max_length {
-            return clique.into_iter().sorted().join(",");
-        }
+    for node in find_largest_clique(&lan) {
+        lan.set_group(lan.id(&node).unwrap(), 0);
+    }
+
+    lan.save(output_path);
+}
+
+fn part_2(file_path: String) -> String {
+    let lan = parse_data(file_path);
+
+    let clique = find_largest_clique(&lan);
+
+    if clique.is_empty() {
+        return "Oh no!".to_string();
     }
 
-    "Oh no!".to_string()
+    clique.join(",")
 }
 
 #[cfg(test)]
@@ -160,6 +164,7 @@ mod tests {
     use super::*;
     use crate::file_utilities::get_file_path;
     use rstest::rstest;
+    use std::collections::HashMap;
 
     #[rstest]
     #[case(true, "7")]
@@ -174,4 +179,85 @@ mod tests {
     fn test_part_2(#[case] is_test: bool, #[case] expected: String) {
         assert_eq!(expected, part_2(get_file_path(is_test, 23, None)));
     }
+
+    // Same as before the names were interned, to compare against.
+    fn bron_kerbosch_by_name(
+        r: &HashSet<String>,
+        p: &mut HashSet<String>,
+        x: &mut HashSet<String>,
+        edges: &HashMap<String, HashSet<String>>,
+        maximal_cliques: &mut Vec<HashSet<String>>,
+    ) {
+        if p.is_empty() && x.is_empty() {
+            maximal_cliques.push(r.clone());
+            return;
+        }
+
+        for v in p.clone() {
+            let mut new_r = r.clone();
+            new_r.insert(v.clone());
+
+            let mut new_p = p.intersection(&edges[&v]).cloned().collect();
+            let mut new_x = x.intersection(&edges[&v]).cloned().collect();
+
+            bron_kerbosch_by_name(&new_r, &mut new_p, &mut new_x, edges, maximal_cliques);
+
+            p.remove(&v);
+            x.insert(v);
+        }
+    }
+
+    // cargo test --release bench_cliques -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_cliques() {
+        use std::time::Instant;
+
+        let names = ('a'..='z')
+            .cartesian_product('a'..='z')
+            .map(|(first, second)| format!("{first}{second}"))
+            .collect_vec();
+
+        // A deterministic scatter of links, and one planted 14-clique.
+        let links = (0..4000)
+            .map(|index| ((index * 7919) % 676, (index * 104729 + 1) % 676))
+            .chain((0..14).tuple_combinations().map(|(i, j)| (i * 47, j * 47)))
+            .filter(|(first, second)| first != second)
+            .map(|(first, second)| (names[first].clone(), names[second].clone()))
+            .collect_vec();
+
+        let start = Instant::now();
+        let mut edges: HashMap<String, HashSet<String>> = HashMap::new();
+        for (first, second) in links.iter() {
+            edges
+                .entry(first.clone())
+                .or_default()
+                .insert(second.clone());
+            edges
+                .entry(second.clone())
+                .or_default()
+                .insert(first.clone());
+        }
+
+        let mut cliques = vec![];
+        let mut p = edges.keys().cloned().collect();
+        bron_kerbosch_by_name(
+            &HashSet::new(),
+            &mut p,
+            &mut HashSet::new(),
+            &edges,
+            &mut cliques,
+        );
+        let largest = cliques.iter().map(|clique| clique.len()).max().unwrap();
+        println!("By name: {:?} ({largest})", start.elapsed());
+
+        let start = Instant::now();
+        let mut lan = Graph::new_undirected();
+        for (first, second) in links.iter() {
+            lan.add_edge(first.clone(), second.clone());
+        }
+
+        let largest = find_largest_clique(&lan).len();
+        println!("By id:   {:?} ({largest})", start.elapsed());
+    }
 }
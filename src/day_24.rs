@@ -1,9 +1,13 @@
 use crate::file_utilities::read_two_chunks;
+use crate::graph_utilities::{Graph, Interner};
+use crate::random_utilities::Random;
+use crate::repl_utilities::{parse_argument, run_interactive, Session};
 
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
 
 use itertools::Itertools;
-use crate::day_24::Gate::Xor;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum Gate {
@@ -52,12 +56,9 @@ fn parse_gate_line(line: String) -> (String, (String, Gate, String)) {
     (output, (input_1, gate, input_2))
 }
 
-fn parse_data(
-    file_path: String,
-) -> (
-    HashMap<String, usize>,
-    HashMap<String, (String, Gate, String)>,
-) {
+type ConnectedGates = HashMap<String, (String, Gate, String)>;
+
+fn parse_data(file_path: String) -> (HashMap<String, usize>, ConnectedGates) {
     let (input_lines, gate_lines) = read_two_chunks(file_path);
 
     let inputs = input_lines
@@ -74,79 +75,332 @@ fn parse_data(
 }
 
 #[allow(dead_code)]
-pub fn run(file_path: String, part: i32) -> usize {
+pub fn run(file_path: String, part: i32) -> String {
     match part {
-        1 => part_1(file_path),
+        1 => part_1(file_path).to_string(),
         2 => part_2(file_path),
         _ => panic!("... nope."),
     }
 }
 
-fn calculate_output(
-    known_registers: &HashMap<String, usize>,
-    connected_gates: &HashMap<String, (String, Gate, String)>,
-) -> usize {
-    let mut known_registers = known_registers.clone();
+// Wire names interned to dense ids, so evaluating (and swapping gates around) never has to
+// touch a string.
+#[derive(Clone, Debug)]
+struct Circuit {
+    wires: Interner<String>,
+    // The gate driving every wire, if any.
+    gates: Vec<Option<(usize, Gate, usize)>>,
+    inputs: Vec<Option<usize>>,
+    // The z wires, most significant first.
+    outputs: Vec<usize>,
+}
+
+impl Circuit {
+    fn new(known_registers: &HashMap<String, usize>, connected_gates: &ConnectedGates) -> Self {
+        let mut wires = Interner::new();
 
-    let all_registers: HashSet<String> = connected_gates
-        .iter()
-        .flat_map(|(key, value)| vec![key.clone(), value.0.clone(), value.2.clone()])
-        .collect();
-
-    // println!("{:?}", all_registers);
-
-    while known_registers.len() != all_registers.len() {
-        let known_register_map: HashSet<String> = known_registers.keys().cloned().collect();
-        let unknown_registers = all_registers.difference(&known_register_map).collect_vec();
-        // println!("I have {} unknown registers left", unknown_registers.len());
-        let mut found_match = false;
-
-        for unknown_register in unknown_registers {
-            let (from, gate, to) = connected_gates.get(unknown_register).unwrap();
-            if let Some(from_value) = known_registers.get(from) {
-                if let Some(to_value) = known_registers.get(to) {
-                    let new_value = gate.calculate(*from_value, *to_value);
-                    known_registers.insert(unknown_register.clone(), new_value);
-                    found_match = true;
+        // Sorted, so the ids don't depend on hash map order.
+        let names = connected_gates
+            .iter()
+            .flat_map(|(output, (input_1, _, input_2))| [output, input_1, input_2])
+            .chain(known_registers.keys())
+            .unique()
+            .sorted();
+
+        for name in names {
+            wires.intern(name.clone());
+        }
+
+        let mut gates = vec![None; wires.len()];
+        let mut inputs = vec![None; wires.len()];
+
+        for (output, (input_1, gate, input_2)) in connected_gates.iter() {
+            gates[wires.get(output).unwrap()] = Some((
+                wires.get(input_1).unwrap(),
+                *gate,
+                wires.get(input_2).unwrap(),
+            ));
+        }
+
+        for (register, value) in known_registers.iter() {
+            inputs[wires.get(register).unwrap()] = Some(*value);
+        }
+
+        let outputs = wires
+            .iter()
+            .filter(|(_, name)| name.starts_with("z"))
+            .map(|(id, _)| id)
+            .rev()
+            .collect_vec();
+
+        Self {
+            wires,
+            gates,
+            inputs,
+            outputs,
+        }
+    }
+
+    fn swap_outputs(&mut self, first: usize, second: usize) {
+        self.gates.swap(first, second);
+    }
+
+    // Spreads the number over the wires with this prefix, e.g. bit 3 of x goes on x03. Wires
+    // like xyz aren't bits of x, so they're left alone.
+    fn set_number(&mut self, prefix: &str, value: usize) {
+        for (id, name) in self.wires.iter() {
+            let Some(Ok(bit)) = name.strip_prefix(prefix).map(str::parse::<u32>) else {
+                continue;
+            };
+
+            self.inputs[id] = Some(value.checked_shr(bit).unwrap_or(0) & 1);
+        }
+    }
+
+    fn get_output_wire(&self, bit: usize) -> Option<usize> {
+        self.outputs.iter().rev().nth(bit).copied()
+    }
+
+    // Every wire driven by a gate that this one depends on, itself included.
+    fn get_dependencies(&self, wire: usize) -> HashSet<usize> {
+        let mut dependencies = HashSet::new();
+        let mut stack = vec![wire];
+
+        while let Some(wire) = stack.pop() {
+            if self.inputs[wire].is_some() {
+                continue;
+            }
+
+            if let Some((input_1, _, input_2)) = self.gates[wire] {
+                if dependencies.insert(wire) {
+                    stack.extend([input_1, input_2]);
                 }
             }
         }
 
-        if !found_match {
-            // println!("I'm stuck!");
-            return 0;
+        dependencies
+    }
+
+    // Puts the gates in an order where every gate comes after the ones driving its inputs, and
+    // only keeps the ones the z wires depend on.
+    fn compile(&self) -> Result<Netlist, String> {
+        let mut visits = vec![Visit::New; self.wires.len()];
+        let mut path = vec![];
+        let mut gates = vec![];
+
+        for output in self.outputs.iter() {
+            self.visit(*output, &mut visits, &mut path, &mut gates)?;
+        }
+
+        let get_wires = |prefix: &str| {
+            self.wires
+                .iter()
+                .filter(|(_, name)| name.starts_with(prefix))
+                .map(|(id, _)| id)
+                .collect_vec()
+        };
+
+        Ok(Netlist {
+            wire_count: self.wires.len(),
+            gates,
+            x_inputs: get_wires("x"),
+            y_inputs: get_wires("y"),
+            outputs: self.outputs.clone(),
+        })
+    }
+
+    fn visit(
+        &self,
+        wire: usize,
+        visits: &mut [Visit],
+        path: &mut Vec<usize>,
+        gates: &mut Vec<(usize, usize, Gate, usize)>,
+    ) -> Result<(), String> {
+        match visits[wire] {
+            Visit::Done => return Ok(()),
+            Visit::InProgress => {
+                let start = path.iter().position(|other| *other == wire).unwrap();
+                let name = self.wires.resolve(wire);
+
+                return match path[start + 1..] {
+                    [] => Err(format!("{name} is driven by itself.")),
+                    ref through => Err(format!(
+                        "{name} is driven by itself, through {}.",
+                        through
+                            .iter()
+                            .map(|other| self.wires.resolve(*other))
+                            .join(", ")
+                    )),
+                };
+            }
+            Visit::New => {}
+        }
+
+        // A known value wins over a gate, the same as when it's set by hand.
+        let gate = match (self.inputs[wire], self.gates[wire]) {
+            (Some(_), _) => None,
+            (None, Some(gate)) => Some(gate),
+            (None, None) => {
+                return Err(format!(
+                    "{} isn't an input, and no gate drives it.",
+                    self.wires.resolve(wire)
+                ))
+            }
+        };
+
+        if let Some((input_1, gate, input_2)) = gate {
+            visits[wire] = Visit::InProgress;
+            path.push(wire);
+
+            self.visit(input_1, visits, path, gates)?;
+            self.visit(input_2, visits, path, gates)?;
+
+            path.pop();
+            gates.push((wire, input_1, gate, input_2));
         }
+
+        visits[wire] = Visit::Done;
+        Ok(())
     }
 
-    let output_keys = known_registers
-        .keys()
-        .filter(|key| key.starts_with("z"))
-        .sorted()
-        .rev()
-        .collect_vec();
+    fn calculate_output(&self) -> Result<usize, String> {
+        let netlist = self.compile()?;
 
-    let output = output_keys
-        .into_iter()
-        .map(|key| *known_registers.get(key).unwrap())
-        .map(|value| value.to_string())
-        .collect_vec()
-        .join("");
+        // Only the lowest bit of every word is used.
+        let mut values = self
+            .inputs
+            .iter()
+            .map(|value| value.unwrap_or(0))
+            .collect_vec();
+        netlist.evaluate(&mut values);
 
-    // println!("{output:?}");
-    usize::from_str_radix(output.as_str(), 2).unwrap()
+        Ok(netlist.get_output(&values, 0))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+}
+
+// A circuit boiled down to a list of gates that can be run straight through. Every wire carries a
+// whole word, so one run works out as many sums as there are bits in a usize.
+#[derive(Clone, Debug)]
+struct Netlist {
+    wire_count: usize,
+    // As (output, input_1, gate, input_2).
+    gates: Vec<(usize, usize, Gate, usize)>,
+    // Least significant first.
+    x_inputs: Vec<usize>,
+    y_inputs: Vec<usize>,
+    // Most significant first, like in the circuit.
+    outputs: Vec<usize>,
+}
+
+impl Netlist {
+    fn evaluate(&self, values: &mut [usize]) {
+        for (output, input_1, gate, input_2) in self.gates.iter() {
+            values[*output] = gate.calculate(values[*input_1], values[*input_2]);
+        }
+    }
+
+    fn get_output(&self, values: &[usize], lane: usize) -> usize {
+        self.outputs.iter().fold(0, |output, wire| {
+            (output << 1) | ((values[*wire] >> lane) & 1)
+        })
+    }
+
+    // What the circuit makes of every (x, y), usize::BITS pairs at a time. Any input that isn't
+    // an x or a y is 0.
+    fn add_all(&self, pairs: &[(usize, usize)]) -> Vec<usize> {
+        let mut results = Vec::with_capacity(pairs.len());
+        let mut values = vec![0; self.wire_count];
+
+        for chunk in pairs.chunks(usize::BITS as usize) {
+            for (bit, wire) in self.x_inputs.iter().enumerate() {
+                values[*wire] = get_lanes(chunk.iter().map(|(x, _)| *x), bit);
+            }
+
+            for (bit, wire) in self.y_inputs.iter().enumerate() {
+                values[*wire] = get_lanes(chunk.iter().map(|(_, y)| *y), bit);
+            }
+
+            self.evaluate(&mut values);
+
+            results.extend((0..chunk.len()).map(|lane| self.get_output(&values, lane)));
+        }
+
+        results
+    }
+}
+
+// The same bit of every number, as a word with one number per lane.
+fn get_lanes(numbers: impl Iterator<Item = usize>, bit: usize) -> usize {
+    numbers.enumerate().fold(0, |word, (lane, number)| {
+        word | (((number >> bit) & 1) << lane)
+    })
+}
+
+// Sums that exercise every bit on its own, and every carry chain from the bottom up.
+fn get_adder_checks(bits: usize) -> Vec<(usize, usize)> {
+    (0..bits)
+        .flat_map(|bit| {
+            let one = 1 << bit;
+            let ones = (one << 1) - 1;
+
+            [
+                (one, 0),
+                (0, one),
+                (one, one),
+                (ones, 1),
+                (1, ones),
+                (ones, ones),
+            ]
+        })
+        .unique()
+        .collect()
 }
 
 fn part_1(file_path: String) -> usize {
     let (known_registers, connected_gates) = parse_data(file_path);
-    calculate_output(&known_registers, &connected_gates)
+
+    Circuit::new(&known_registers, &connected_gates)
+        .calculate_output()
+        .unwrap_or_else(|error| panic!("{error}"))
+}
+
+// Wires are nodes and every gate is drawn on the wire it drives, inputs and outputs in their own
+// clusters.
+pub fn export_graph(file_path: String, output_path: String) {
+    let (_, connected_gates) = parse_data(file_path);
+
+    let mut graph = Graph::new_directed();
+
+    for (output, (input_1, gate, input_2)) in connected_gates.into_iter().sorted() {
+        let (_, output_id) = graph.add_edge(input_1, output.clone());
+        graph.add_edge(input_2, output.clone());
+        graph.set_label(output_id, format!("{output} ({gate:?})"));
+    }
+
+    for id in graph.node_ids().collect_vec() {
+        match &graph.node(id)[0..1] {
+            "x" | "y" => graph.set_group(id, 0),
+            "z" => graph.set_group(id, 1),
+            _ => {}
+        }
+    }
+
+    graph.save(output_path);
 }
 
 fn get_standard_adder(
-    x_inputs: &Vec<String>,
-    y_inputs: &Vec<String>,
+    x_inputs: &[String],
+    y_inputs: &[String],
 ) -> Vec<(String, (String, Gate, String))> {
-    let x_inputs = x_inputs.clone();
-    let y_inputs = y_inputs.clone();
+    let x_inputs = x_inputs.to_vec();
+    let y_inputs = y_inputs.to_vec();
 
     let total_input_bits = x_inputs.len();
 
@@ -161,12 +415,7 @@ fn get_standard_adder(
         ),
     ];
 
-    for (index, (x_input, y_input)) in x_inputs
-        .into_iter()
-        .zip(y_inputs.into_iter())
-        .enumerate()
-        .skip(1)
-    {
+    for (index, (x_input, y_input)) in x_inputs.into_iter().zip(y_inputs).enumerate().skip(1) {
         let prev_index_string = format!("{:0>2}", index - 1);
         let prev_carry = format!("c{prev_index_string}");
 
@@ -179,7 +428,7 @@ fn get_standard_adder(
         // The last carry is just the last z.
         let carry = if index == total_input_bits - 1 {
             let next_index_string = format!("{:0>2}", index + 1);
-            format!("s{next_index_string}")
+            format!("z{next_index_string}")
         } else {
             format!("c{index_string}")
         };
@@ -207,396 +456,406 @@ fn get_standard_adder(
     adder
 }
 
-fn part_2(file_path: String) -> usize {
-    let (known_registers, connected_gates) = parse_data(file_path);
-    let output = calculate_output(&known_registers, &connected_gates);
-
-    let x_inputs = known_registers
-        .keys()
-        .filter(|key| key.starts_with("x"))
-        .sorted()
-        .cloned()
-        .collect_vec();
-
-    let x = x_inputs
-        .clone()
-        .into_iter()
-        .rev()
-        .map(|key| *known_registers.get(&key).unwrap())
-        .map(|value| value.to_string())
-        .collect_vec()
-        .join("");
-
-    let x = usize::from_str_radix(x.as_str(), 2).unwrap();
+// Where the circuit first stops looking like the standard adder, and the wires whose gates are
+// probably the wrong way around there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Divergence {
+    bit: usize,
+    // The gate the standard adder has, in terms of the circuit's wires.
+    expected: String,
+    suspects: Vec<String>,
+}
 
-    let y_inputs = known_registers
-        .keys()
-        .filter(|key| key.starts_with("y"))
-        .sorted()
-        .cloned()
-        .collect_vec();
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bit {} should have {}, so look at the gates driving {}.",
+            self.bit,
+            self.expected,
+            self.suspects.join(", ")
+        )
+    }
+}
 
-    let y = y_inputs
-        .clone()
-        .into_iter()
-        .rev()
-        .map(|key| *known_registers.get(&key).unwrap())
-        .map(|value| value.to_string())
-        .collect_vec()
-        .join("");
-
-    let y = usize::from_str_radix(y.as_str(), 2).unwrap();
-
-    let expected_output = x + y;
-
-    println!("x is {x:b} and y is {y:b} and we should get {expected_output:b} but get {output:b}");
-
-    // let mut known_registers = known_registers;
-    //
-    // for y_input in y_inputs {
-    //     if y_input == "y00" {
-    //         known_registers.insert(y_input.clone(), 1);
-    //     } else {
-    //         known_registers.insert(y_input.clone(), 0);
-    //     }
-    // }
-    //
-    // let temp = x_inputs.len();
-    //
-    // for x in 0..2_usize.pow(10 as u32) {
-    //     let x_binary = format!("{x:b}");
-    //     // println!("{x}: {x_binary}");
-    //
-    //     for (index, char) in x_binary.chars().rev().enumerate() {
-    //         let index_string = format!("{index:0>2}");
-    //         let x_string = format!("x{index_string}");
-    //         let value = char.to_digit(10).unwrap() as usize;
-    //
-    //         // println!("{x_string} is put on {value}");
-    //         known_registers.insert(x_string, value);
-    //     }
-    //
-    //     for index in x_binary.len()..temp {
-    //         let index_string = format!("{index:0>2}");
-    //         let x_string = format!("x{index_string}");
-    //
-    //         // println!("{x_string} is put on 0");
-    //         known_registers.insert(x_string, 0);
-    //     }
-    //
-    //     let output = calculate_output(&known_registers, &connected_gates);
-    //     let expected_output = x + 1;
-    //
-    //     if output != expected_output {
-    //         println!("When x = {x} ({x_binary}), we get {output} instead of {expected_output}");
-    //         break;
-    //     }
-    // }
-
-    // println!("{x_inputs:?}");
-    // println!("{y_inputs:?}");
-    //
-    // I'm almost certain this is a standard binary adder circuit,
-    // so I can program how it's supposed to look and find the mismatches.
-    let adder: HashMap<String, (String, Gate, String)> = get_standard_adder(&x_inputs, &y_inputs)
-        .into_iter()
-        .collect();
-
-    // println!("Expected");
-    // for (key, value) in adder.iter() {
-    //     println!("{key:?}: {value:?}");
-    // }
-    //
-    // println!("Actual");
-    // for (key, value) in connected_gates.iter() {
-    //     println!("{key:?}: {value:?}");
-    // }
-
-    let z_outputs = adder
-        .keys()
-        .filter(|key| key.starts_with("z"))
-        .sorted()
-        .collect_vec();
+// Goes through the standard adder gate by gate, finding the circuit's gate with the same kind and
+// the same inputs, whatever they're called and whichever way around they are.
+fn compare_to_adder(circuit: &Circuit) -> Option<Divergence> {
+    let get_inputs = |prefix: &str| {
+        circuit
+            .wires
+            .iter()
+            .filter(|(_, name)| name.starts_with(prefix))
+            .map(|(_, name)| name.clone())
+            .collect_vec()
+    };
+
+    let x_inputs = get_inputs("x");
+    let y_inputs = get_inputs("y");
+
+    let gates_by_inputs = circuit
+        .gates
+        .iter()
+        .enumerate()
+        .filter_map(|(output, gate)| {
+            gate.map(|(input_1, gate, input_2)| {
+                ((input_1.min(input_2), gate, input_1.max(input_2)), output)
+            })
+        })
+        .collect::<HashMap<_, _>>();
+
+    // From the standard adder's wires to the circuit's.
+    let mut matches = x_inputs
+        .iter()
+        .chain(y_inputs.iter())
+        .map(|name| (name.clone(), circuit.wires.get(name).unwrap()))
+        .collect::<HashMap<_, _>>();
+
+    let get_names = |wires: &[usize]| {
+        wires
+            .iter()
+            .map(|wire| circuit.wires.resolve(*wire).clone())
+            .sorted()
+            .collect_vec()
+    };
+
+    for (output, (input_1, gate, input_2)) in get_standard_adder(&x_inputs, &y_inputs) {
+        let bit = output[1..].parse::<usize>().unwrap();
+        let (input_1, input_2) = (matches[&input_1], matches[&input_2]);
+        let is_output = output.starts_with("z");
+
+        let expected = format!(
+            "{} {gate:?} {}{}",
+            circuit.wires.resolve(input_1),
+            circuit.wires.resolve(input_2),
+            if is_output {
+                format!(" -> {output}")
+            } else {
+                String::new()
+            }
+        );
+
+        let key = (input_1.min(input_2), gate, input_1.max(input_2));
+
+        let Some(actual) = gates_by_inputs.get(&key).copied() else {
+            // Usually one input is right, so the other one and whatever the right gate uses
+            // instead are the ones to look at.
+            let suspects = circuit
+                .gates
+                .iter()
+                .flatten()
+                .filter(|(_, other_gate, _)| *other_gate == gate)
+                .find_map(|(first, _, second)| match (*first, *second) {
+                    (first, second) | (second, first) if first == input_1 => {
+                        Some([input_2, second])
+                    }
+                    (first, second) | (second, first) if first == input_2 => {
+                        Some([input_1, second])
+                    }
+                    _ => None,
+                })
+                .unwrap_or([input_1, input_2]);
+
+            return Some(Divergence {
+                bit,
+                expected,
+                suspects: get_names(&suspects),
+            });
+        };
 
-    for z_output in z_outputs.iter() {
-        let original = connected_gates.get(*z_output).unwrap();
-        let expected = adder.get(*z_output).unwrap();
+        let actual_name = circuit.wires.resolve(actual);
 
-        if original.1 != expected.1 {
-            println!("Mismatch {z_output:?}: {original:?} vs expected {expected:?}");
+        if is_output && *actual_name != output {
+            let suspects = vec![actual_name.clone(), output];
+            return Some(Divergence {
+                bit,
+                expected,
+                suspects: suspects.into_iter().sorted().collect(),
+            });
         }
-    }
-
-    let reverse_connected_gates: HashMap<(String, Gate, String), String> = connected_gates
-        .iter()
-        .map(|(key, value)| (value.clone(), key.clone()))
-        .collect();
 
-    let reverse_adder: HashMap<(String, Gate, String), String> = adder
-        .iter()
-        .map(|(key, value)| (value.clone(), key.clone()))
-        .collect();
+        if !is_output && actual_name.starts_with("z") {
+            return Some(Divergence {
+                bit,
+                expected,
+                suspects: vec![actual_name.clone()],
+            });
+        }
 
-    for (x_input, y_input) in x_inputs.into_iter().zip(y_inputs.into_iter()).skip(1) {
-        let xor_1 = (x_input.clone(), Gate::Xor, y_input.clone());
-        let xor_2 = (y_input.clone(), Gate::Xor, x_input.clone());
+        matches.insert(output, actual);
+    }
 
-        let and_1 = (x_input.clone(), Gate::And, y_input.clone());
-        let and_2 = (y_input.clone(), Gate::And, x_input.clone());
+    None
+}
 
-        let xor_origin = reverse_connected_gates.get(&xor_1).unwrap_or_else(|| reverse_connected_gates.get(&xor_2).unwrap());
-        let xor_expected = reverse_adder.get(&xor_1).unwrap_or_else(|| reverse_adder.get(&xor_2).unwrap());
+// Pseudo-random, but the same every time.
+fn get_random_pairs(count: usize, bits: usize) -> Vec<(usize, usize)> {
+    let mut random = Random::new(0x9e3779b97f4a7c15);
+    let mut next = || random.get_bits(bits as u32) as usize;
 
-        let and_origin = reverse_connected_gates.get(&and_1).unwrap_or_else(|| reverse_connected_gates.get(&and_2).unwrap());
-        let and_expected = reverse_adder.get(&and_1).unwrap_or_else(|| reverse_adder.get(&and_2).unwrap());
+    (0..count).map(|_| (next(), next())).collect()
+}
 
-        if z_outputs.contains(&xor_origin){
-            println!("Mismatch {xor_1:?} or {xor_2:?}: {xor_origin:?} vs expected {xor_expected:?}");
-            // Mismatch ("x00", Xor, "y00") or ("y00", Xor, "x00"): "z00" vs expected "z00"
-        }
+// Going by the checks, which are in order of the highest bit they use.
+fn get_first_wrong_bit(netlist: &Netlist, checks: &[(usize, usize)]) -> Option<usize> {
+    checks
+        .iter()
+        .zip(netlist.add_all(checks))
+        .find(|((x, y), output)| x + y != *output)
+        .map(|((x, y), _)| (x | y).ilog2() as usize)
+}
 
-        if z_outputs.contains(&and_origin) {
-            println!("Mismatch {and_1:?} or {and_2:?}: {and_origin:?} vs expected {and_expected:?}");
-            // Mismatch ("x28", And, "y28") or ("y28", And, "x28"): "z28" vs expected "a28"
-        }
-    }
+const SWAP_COUNT: usize = 4;
 
-    for ((x, gate, y), z) in reverse_connected_gates.iter() {
-        if *gate != Gate::Xor {
-            continue;
-        }
+// Fixes the lowest wrong bit first. One of the swapped wires has to be something only the z wires
+// from that bit up depend on, and a swap only counts if it gets the circuit further. Leaves the
+// swaps in place if it finds them.
+fn find_swaps(
+    circuit: &mut Circuit,
+    checks: &[(usize, usize)],
+    swaps_left: usize,
+) -> Option<Vec<(usize, usize)>> {
+    let netlist = circuit.compile().ok()?;
 
-        let x_and_y = x.starts_with("x") && y.starts_with("y");
-        let y_and_x = x.starts_with("y") && y.starts_with("x");
+    let Some(bit) = get_first_wrong_bit(&netlist, checks) else {
+        return Some(vec![]);
+    };
 
-        if !z.starts_with("z") && !x_and_y && !y_and_x {
-            println!("Mismatch for {:?}", ((x, gate, y), z));
-        }
+    if swaps_left == 0 {
+        return None;
     }
 
-    let mut temporary_sums = vec![];
-    let mut temporary_carry_1s = vec![];
+    let get_dependencies = |bits: Range<usize>| {
+        bits.filter_map(|bit| circuit.get_output_wire(bit))
+            .flat_map(|wire| circuit.get_dependencies(wire))
+            .collect::<HashSet<_>>()
+    };
 
-    for ((input_1, gate, input_2), output) in reverse_connected_gates.iter() {
-        let output_is_z = output.starts_with("z");
-        let input_is_x_and_y = input_1.starts_with("x") && input_2.starts_with("y");
-        let input_is_y_and_x = input_1.starts_with("y") && input_2.starts_with("x");
-
-        match gate {
-            Gate::Xor => {
-                // A Xor should either go to temporay sum (with x and y) or an output.
-                if input_is_y_and_x || input_is_x_and_y {
-                    temporary_sums.push(((input_1.clone(), gate.clone(), input_2.clone()), output));
-                }
-                if output_is_z && (input_is_x_and_y || input_is_y_and_x) {
-                    println!("{:?} is incorrect because output should not be Z!", ((input_1, gate, input_2), output));
-                    continue;
-                }
+    let lower = get_dependencies(0..bit);
+    let suspects = get_dependencies(bit..bit + 2)
+        .into_iter()
+        .filter(|wire| !lower.contains(wire))
+        .sorted();
 
-                if !input_is_x_and_y && !input_is_y_and_x {
-                    if !output_is_z {
-                        println!("{:?} is incorrect because output should be Z!", ((input_1, gate, input_2), output));
-                    }
-                }
-            },
-            Gate::And => {
-                if input_is_y_and_x || input_is_x_and_y {
-                    temporary_carry_1s.push(((input_1.clone(), gate.clone(), input_2.clone()), output.clone()));
-                }
+    let gate_wires = (0..circuit.gates.len())
+        .filter(|wire| circuit.gates[*wire].is_some())
+        .collect_vec();
 
-                // An And has to either be an x and y going into not z
-                if output_is_z {
-                    println!("{:?} is incorrect because output should not be Z!", ((input_1, gate, input_2), output));
-                    continue;
-                }
+    let pairs = suspects
+        .cartesian_product(gate_wires)
+        .filter(|(first, second)| first != second)
+        .map(|(first, second)| (first.min(second), first.max(second)))
+        .unique()
+        .collect_vec();
 
-                // Or if it's not x or y, one of the inputs should be a previous carry.
-                if !input_is_x_and_y && !input_is_y_and_x {
-                    let (input_1_input_1, input_1_gate, input_1_input_2) = connected_gates.get(input_1).unwrap();
-                    let (input_2_input_1, input_2_gate, input_2_input_2) = connected_gates.get(input_2).unwrap();
+    let mut swaps = vec![];
 
-                    if *input_1_gate == Gate::Xor && *input_2_gate == Gate::Or {
-                        continue;
-                    }
+    for (first, second) in pairs {
+        circuit.swap_outputs(first, second);
 
-                    if *input_2_gate == Gate::Xor && *input_1_gate == Gate::Or {
-                        continue;
-                    }
+        if let Ok(netlist) = circuit.compile() {
+            let next_bit = get_first_wrong_bit(&netlist, checks).unwrap_or(usize::MAX);
 
-                    println!("{:?} is incorrect because inputs should be one from OR and one from XOR!", ((input_1, gate, input_2), output));
-                    println!("    and instead I have {input_1} from {input_1_gate:?}, and {input_2} from {input_2_gate:?}");
-                }
-            },
-            Gate::Or => {
-                // An OR has to be output carry and input two temp_carries, i.e. no x, y or z.
-                if output_is_z && (input_is_x_and_y || input_is_y_and_x) {
-                    println!("{:?} is incorrect!", ((input_1, gate, input_2), output));
-                }
+            if next_bit > bit {
+                swaps.push((next_bit, first, second));
             }
         }
-    }
 
-    println!("Temporary sums: {temporary_sums:?}");
-    println!("Temporary carry 1s: {temporary_carry_1s:?}");
-
-    let mut temporary_carry_2s = vec![];
+        circuit.swap_outputs(first, second);
+    }
 
-    let temp1_s: HashSet<String> = temporary_carry_1s.iter().map(|(_, v)| v.clone()).collect();
+    trace!("{} swaps get past bit {bit}.", swaps.len());
 
-    for ((input_1, gate, input_2), output) in reverse_connected_gates.iter() {
-        let output_is_z = output.starts_with("z");
-        let input_is_x_and_y = input_1.starts_with("x") && input_2.starts_with("y");
-        let input_is_y_and_x = input_1.starts_with("y") && input_2.starts_with("x");
+    // The ones that get furthest first.
+    for (_, first, second) in swaps.into_iter().sorted().rev() {
+        circuit.swap_outputs(first, second);
 
-        if *gate != Gate::Or {
-            continue;
+        if let Some(mut rest) = find_swaps(circuit, checks, swaps_left - 1) {
+            rest.push((first, second));
+            return Some(rest);
         }
 
-        if temp1_s.contains(input_1) {
-            temporary_carry_2s.push(input_2.clone());
-        } else if temp1_s.contains(input_2) {
-            temporary_carry_2s.push(input_2.clone());
-        } else {
-            println!("I don't think {:?} is correct", ((input_1, gate, input_2), output))
-        }
+        circuit.swap_outputs(first, second);
+    }
 
+    None
+}
 
-    }
+// The names of every wire that had to be swapped for the circuit to add x and y, in order.
+fn repair_adder(circuit: &mut Circuit) -> Result<Vec<String>, String> {
+    let bits = circuit
+        .wires
+        .iter()
+        .filter(|(_, name)| name.starts_with("x"))
+        .count();
 
-    println!("Temporary carry 2s: {temporary_carry_2s:?}");
+    if bits == 0 {
+        return Err("There are no x wires, so there's nothing to add.".to_string());
+    }
 
-    // return 0;
+    let swaps = find_swaps(circuit, &get_adder_checks(bits), SWAP_COUNT).ok_or_else(|| {
+        format!("Swapping up to {SWAP_COUNT} pairs of wires doesn't make it add.")
+    })?;
 
-    let mut result = HashSet::new();
+    for (first, second) in swaps.iter() {
+        debug!(
+            "Swapped {} and {}.",
+            circuit.wires.resolve(*first),
+            circuit.wires.resolve(*second)
+        );
+    }
 
-    let known_problems = vec!["z08", "z28", "z39", "vvr", "mqh", "tfb"].into_iter().map(|s| s.to_string()).collect_vec();
-    let extra_problems = vec!["qvn", "sgr", "drw", "pvv", "rnq", "mcc", "ggf", "rqg", "gws", "tfb", "vst", "bkr", "kbg"].into_iter().map(|s| s.to_string()).collect_vec();
+    // Anything the checks missed should show up here.
+    let pairs = get_random_pairs(1000, bits);
+    let netlist = circuit.compile()?;
 
-    let mut option_checked = 0;
+    if let Some(((x, y), output)) = pairs
+        .iter()
+        .zip(netlist.add_all(&pairs))
+        .find(|((x, y), output)| x + y != *output)
+    {
+        let error = format!("Even after swapping, {x} + {y} gives {output}.");
 
-    for extra_problem_1 in extra_problems.iter() {
-        for extra_problem_2 in extra_problems.iter() {
-            let extra = vec![extra_problem_1.clone(), extra_problem_2.clone()];
-            let all_problems: Vec<String> = known_problems.iter().chain(extra.iter()).cloned().collect_vec();
+        for (first, second) in swaps.into_iter().rev() {
+            circuit.swap_outputs(first, second);
+        }
 
-            if all_problems.iter().unique().count() < 8 {
-                continue;
-            }
+        return Err(error);
+    }
 
-            // println!("Checking problems {all_problems:?}");
+    Ok(swaps
+        .into_iter()
+        .flat_map(|(first, second)| [first, second])
+        .map(|wire| circuit.wires.resolve(wire).clone())
+        .sorted()
+        .collect())
+}
 
-            for (t1_1, t2_1, t3_1, t4_1) in all_problems.iter().cloned().tuple_combinations::<(_,_,_,_)>() {
-                let less_problems: Vec<String> = all_problems
-                    .iter()
-                    .filter(|a| **a != t1_1 && **a != t2_1 && **a != t3_1 && **a != t4_1)
-                    .cloned()
-                    .collect_vec();
+// The answer, or why there isn't one.
+fn get_swapped_wires(circuit: &mut Circuit) -> String {
+    match repair_adder(circuit) {
+        Ok(wires) => wires.join(","),
+        Err(error) => format!("No solution: {error}"),
+    }
+}
 
-                for ts_2 in less_problems.iter().permutations(4) {
-                    let tuple_1 = (t1_1.clone(), ts_2[0].clone());
-                    let tuple_2 = (t2_1.clone(), ts_2[1].clone());
-                    let tuple_3 = (t3_1.clone(), ts_2[2].clone());
-                    let tuple_4 = (t4_1.clone(), ts_2[3].clone());
+fn part_2(file_path: String) -> String {
+    let (known_registers, connected_gates) = parse_data(file_path);
+    let mut circuit = Circuit::new(&known_registers, &connected_gates);
 
-                    option_checked += 1;
+    get_swapped_wires(&mut circuit)
+}
 
-                    // if option_checked % 1000 == 0 {
-                    //     println!(
-                    //         "Checking option {option_checked} (out of tuples {}?) {tuple_1:?}, {tuple_2:?}, {tuple_3:?}, {tuple_4:?}",
-                    //         720 * extra_problems.len() * extra_problems.len(),
-                    //     );
-                    // }
+struct CircuitSession {
+    circuit: Circuit,
+}
 
-                    let mut altered_connected_gates = connected_gates.clone();
+impl CircuitSession {
+    fn get_wire(&self, arguments: &[&str], index: usize) -> Result<usize, String> {
+        let name = parse_argument::<String>(arguments, index, "wire")?;
 
-                    let t_1_0 = altered_connected_gates.remove(&tuple_1.0).unwrap();
-                    let t_1_1 = altered_connected_gates.remove(&tuple_1.1).unwrap();
+        self.circuit
+            .wires
+            .get(&name)
+            .ok_or_else(|| format!("There's no wire {name}."))
+    }
+}
 
-                    altered_connected_gates.insert(tuple_1.0.clone(), t_1_1);
-                    altered_connected_gates.insert(tuple_1.1.clone(), t_1_0);
+impl Session for CircuitSession {
+    fn get_help(&self) -> Vec<&'static str> {
+        vec![
+            "count - shows how many wires and gates there are",
+            "gate <wire> - shows the gate driving the wire",
+            "output - the number on the z wires",
+            "eval <x> <y> - puts x and y on the input wires and shows the number on the z wires",
+            "swap <wire> <wire> - swaps the gates driving the two wires",
+            "check - adds numbers that test every bit and carry, and shows the wrong sums",
+            "compare - shows where the circuit first differs from a standard adder",
+            "repair - swaps the wires that stop the circuit from adding, and shows which",
+        ]
+    }
 
-                    let t_2_0 = altered_connected_gates.remove(&tuple_2.0).unwrap();
-                    let t_2_1 = altered_connected_gates.remove(&tuple_2.1).unwrap();
+    fn handle(&mut self, command: &str, arguments: &[&str]) -> Result<String, String> {
+        match command {
+            "count" => Ok(format!(
+                "{} wires, {} gates",
+                self.circuit.wires.len(),
+                self.circuit.gates.iter().flatten().count()
+            )),
+            "gate" => {
+                let wire = self.get_wire(arguments, 0)?;
+                let name = self.circuit.wires.resolve(wire);
+
+                match self.circuit.gates[wire] {
+                    Some((input_1, gate, input_2)) => Ok(format!(
+                        "{} {gate:?} {} -> {name}",
+                        self.circuit.wires.resolve(input_1),
+                        self.circuit.wires.resolve(input_2)
+                    )),
+                    None => Ok(format!("{name} is an input.")),
+                }
+            }
+            "output" => self
+                .circuit
+                .calculate_output()
+                .map(|output| output.to_string()),
+            "eval" => {
+                let x = parse_argument(arguments, 0, "x")?;
+                let y = parse_argument(arguments, 1, "y")?;
 
-                    altered_connected_gates.insert(tuple_2.0.clone(), t_2_1);
-                    altered_connected_gates.insert(tuple_2.1.clone(), t_2_0);
+                self.circuit.set_number("x", x);
+                self.circuit.set_number("y", y);
 
-                    let t_3_0 = altered_connected_gates.remove(&tuple_3.0).unwrap();
-                    let t_3_1 = altered_connected_gates.remove(&tuple_3.1).unwrap();
+                let output = self.circuit.calculate_output()?;
 
-                    altered_connected_gates.insert(tuple_3.0.clone(), t_3_1);
-                    altered_connected_gates.insert(tuple_3.1.clone(), t_3_0);
+                let sum = x
+                    .checked_add(y)
+                    .ok_or_else(|| "x + y is too big to check.".to_string())?;
 
-                    let t_4_0 = altered_connected_gates.remove(&tuple_4.0).unwrap();
-                    let t_4_1 = altered_connected_gates.remove(&tuple_4.1).unwrap();
+                Ok(format!("{output} ({output:b}), x + y is {sum} ({sum:b})"))
+            }
+            "swap" => {
+                let first = self.get_wire(arguments, 0)?;
+                let second = self.get_wire(arguments, 1)?;
 
-                    altered_connected_gates.insert(tuple_4.0.clone(), t_4_1);
-                    altered_connected_gates.insert(tuple_4.1.clone(), t_4_0);
+                self.circuit.swap_outputs(first, second);
+                Ok(String::new())
+            }
+            "check" => {
+                let netlist = self.circuit.compile()?;
+                let checks = get_adder_checks(netlist.x_inputs.len());
 
-                    let output = calculate_output(&known_registers, &altered_connected_gates);
+                let wrong = checks
+                    .iter()
+                    .zip(netlist.add_all(&checks))
+                    .filter(|((x, y), output)| x + y != *output)
+                    .map(|((x, y), output)| format!("{x:b} + {y:b} gives {output:b}"))
+                    .collect_vec();
 
-                    if output == expected_output {
-                        let temp = all_problems.clone().into_iter().sorted().join(",");
-                        println!("{temp:?} at {option_checked} out of {}", 720 * extra_problems.len() * extra_problems.len());
-                        result.insert(temp);
-                    }
+                match wrong.len() {
+                    0 => Ok(format!("All {} sums are right.", checks.len())),
+                    count => Ok(format!(
+                        "{count} out of {} sums are wrong, like:\n{}",
+                        checks.len(),
+                        wrong.iter().take(5).join("\n")
+                    )),
                 }
             }
-
-            // for tuple_1 in all_problems.iter().cloned().tuple_combinations::<(_,_)>() {
-            //     let less_problems: Vec<String> = all_problems.iter().filter(|a| **a != tuple_1.0 && **a != tuple_1.1).cloned().collect_vec();
-            //
-            //     for tuple_2 in less_problems.iter().cloned().tuple_combinations::<(_,_)>() {
-            //         let even_less_problems: Vec<String> = less_problems.iter().filter(|a| **a != tuple_2.0 && **a != tuple_2.1).cloned().collect_vec();
-            //
-            //         for tuple_3 in even_less_problems.iter().cloned().tuple_combinations::<(_,_)>() {
-            //             let more_even_less_problems: Vec<String> = even_less_problems.iter().filter(|a| **a != tuple_3.0 && **a != tuple_3.1).cloned().collect_vec();
-            //
-            //             let tuple_4 = (more_even_less_problems[0].clone(), more_even_less_problems[1].clone());
-            //
-            //             option_checked += 1;
-            //             println!("Checking option {option_checked} tuples {tuple_1:?}, {tuple_2:?}, {tuple_3:?}, {tuple_4:?}");
-            //
-            //             let mut altered_connected_gates = connected_gates.clone();
-            //
-            //             let t_1_0 = altered_connected_gates.remove(&tuple_1.0).unwrap();
-            //             let t_1_1 = altered_connected_gates.remove(&tuple_1.1).unwrap();
-            //
-            //             altered_connected_gates.insert(tuple_1.0.clone(), t_1_1);
-            //             altered_connected_gates.insert(tuple_1.1.clone(), t_1_0);
-            //
-            //             let t_2_0 = altered_connected_gates.remove(&tuple_2.0).unwrap();
-            //             let t_2_1 = altered_connected_gates.remove(&tuple_2.1).unwrap();
-            //
-            //             altered_connected_gates.insert(tuple_2.0.clone(), t_2_1);
-            //             altered_connected_gates.insert(tuple_2.1.clone(), t_2_0);
-            //
-            //             let t_3_0 = altered_connected_gates.remove(&tuple_3.0).unwrap();
-            //             let t_3_1 = altered_connected_gates.remove(&tuple_3.1).unwrap();
-            //
-            //             altered_connected_gates.insert(tuple_3.0.clone(), t_3_1);
-            //             altered_connected_gates.insert(tuple_3.1.clone(), t_3_0);
-            //
-            //             let t_4_0 = altered_connected_gates.remove(&tuple_4.0).unwrap();
-            //             let t_4_1 = altered_connected_gates.remove(&tuple_4.1).unwrap();
-            //
-            //             altered_connected_gates.insert(tuple_4.0.clone(), t_4_1);
-            //             altered_connected_gates.insert(tuple_4.1.clone(), t_4_0);
-            //
-            //             let output = calculate_output(&known_registers, &altered_connected_gates);
-            //
-            //             if output == expected_output {
-            //                 println!("{all_problems:?}");
-            //                 return 42;
-            //             }
-            //         }
-            //     }
-            // }
+            "compare" => Ok(compare_to_adder(&self.circuit)
+                .map(|divergence| divergence.to_string())
+                .unwrap_or_else(|| "It's a standard adder.".to_string())),
+            "repair" => repair_adder(&mut self.circuit).map(|wires| wires.join(",")),
+            _ => Err(format!("Day 24 doesn't know how to {command}.")),
         }
     }
+}
+
+#[allow(dead_code)]
+pub fn repl(file_path: String) {
+    let (known_registers, connected_gates) = parse_data(file_path);
+    let circuit = Circuit::new(&known_registers, &connected_gates);
 
-    println!("{result:?}");
-    0
+    run_interactive(&mut CircuitSession { circuit });
 }
 
 #[cfg(test)]
@@ -612,9 +871,339 @@ mod tests {
         assert_eq!(expected, part_1(get_file_path(is_test, 24, None)));
     }
 
+    // The answer for the real input isn't checked in, so this checks what it has to be instead:
+    // the swaps really make it add, and include the wires found by hand before there was a solver.
     #[rstest]
-    #[case(false, 0)]
-    fn test_part_2(#[case] is_test: bool, #[case] expected: usize) {
-        assert_eq!(expected, part_2(get_file_path(is_test, 24, None)));
+    #[case(false)]
+    fn test_part_2(#[case] is_test: bool) {
+        let file_path = get_file_path(is_test, 24, None);
+        let answer = part_2(file_path.clone());
+
+        let (known_registers, connected_gates) = parse_data(file_path);
+        let mut circuit = Circuit::new(&known_registers, &connected_gates);
+
+        assert_eq!(answer, get_swapped_wires(&mut circuit));
+        assert_eq!(
+            None,
+            get_first_wrong_bit(&circuit.compile().unwrap(), &get_adder_checks(45))
+        );
+
+        for wire in ["z08", "z28", "z39", "vvr", "mqh", "tfb"] {
+            assert!(answer.split(",").contains(&wire));
+        }
+    }
+
+    #[test]
+    fn test_get_swapped_wires() {
+        let swaps = [
+            ("z03", "a03"),
+            ("s07", "a07"),
+            ("z10", "c10"),
+            ("z13", "b13"),
+        ]
+        .into_iter()
+        .flat_map(|(first, second)| [(first, second), (second, first)])
+        .collect::<HashMap<_, _>>();
+
+        let gates = get_adder_lines(16)
+            .into_iter()
+            .map(|line| {
+                let (gate, output) = line.split_once(" -> ").unwrap();
+                format!("{gate} -> {}", swaps.get(output).unwrap_or(&output))
+            })
+            .collect_vec();
+
+        let mut adder = get_circuit(&[], &gates);
+        adder.set_number("x", 0);
+        adder.set_number("y", 0);
+
+        assert_eq!(
+            "a03,a07,b13,c10,s07,z03,z10,z13",
+            get_swapped_wires(&mut adder)
+        );
+    }
+
+    fn get_circuit(inputs: &[&str], gates: &[String]) -> Circuit {
+        let known_registers = inputs
+            .iter()
+            .map(|line| parse_input_line(line.to_string()))
+            .collect();
+        let connected_gates = gates.iter().cloned().map(parse_gate_line).collect();
+
+        Circuit::new(&known_registers, &connected_gates)
+    }
+
+    // A ripple carry adder, with the last carry on the top z.
+    fn get_adder_lines(bits: usize) -> Vec<String> {
+        let mut lines = vec![
+            "x00 XOR y00 -> z00".to_string(),
+            "x00 AND y00 -> c00".to_string(),
+        ];
+
+        for bit in 1..bits {
+            let carry = if bit == bits - 1 {
+                format!("z{:0>2}", bits)
+            } else {
+                format!("c{bit:0>2}")
+            };
+
+            lines.extend([
+                format!("x{bit:0>2} XOR y{bit:0>2} -> s{bit:0>2}"),
+                format!("s{bit:0>2} XOR c{:0>2} -> z{bit:0>2}", bit - 1),
+                format!("x{bit:0>2} AND y{bit:0>2} -> a{bit:0>2}"),
+                format!("s{bit:0>2} AND c{:0>2} -> b{bit:0>2}", bit - 1),
+                format!("a{bit:0>2} OR b{bit:0>2} -> {carry}"),
+            ]);
+        }
+
+        lines
+    }
+
+    #[test]
+    fn test_calculate_output() {
+        let inputs = ["x00: 1", "x01: 1", "x02: 1", "y00: 0", "y01: 1", "y02: 0"];
+        let gates = [
+            "x00 AND y00 -> z00",
+            "x01 XOR y01 -> z01",
+            "x02 OR y02 -> z02",
+        ]
+        .map(String::from);
+
+        assert_eq!(Ok(4), get_circuit(&inputs, &gates).calculate_output());
+
+        let mut adder = get_circuit(&[], &get_adder_lines(4));
+        adder.set_number("x", 11);
+        adder.set_number("y", 6);
+        assert_eq!(Ok(17), adder.calculate_output());
+
+        // Now s01 is s02 XOR c01, and c01 needs s01 through b01.
+        let s01 = adder.wires.get(&"s01".to_string()).unwrap();
+        let z02 = adder.wires.get(&"z02".to_string()).unwrap();
+
+        adder.swap_outputs(s01, z02);
+        assert_eq!(
+            Err("c01 is driven by itself, through b01, s01.".to_string()),
+            adder.calculate_output()
+        );
+
+        let looped = get_circuit(&["x00: 1"], &["x00 XOR z00 -> z00".to_string()]);
+        assert_eq!(
+            Err("z00 is driven by itself.".to_string()),
+            looped.calculate_output()
+        );
+
+        let undriven = get_circuit(&["x00: 1"], &["x00 XOR abc -> z00".to_string()]);
+        assert_eq!(
+            Err("abc isn't an input, and no gate drives it.".to_string()),
+            undriven.calculate_output()
+        );
+    }
+
+    #[test]
+    fn test_set_number() {
+        let mut circuit = get_circuit(
+            &["x00: 0", "x64: 1", "xyz: 1"],
+            &["x64 XOR xyz -> z00".to_string()],
+        );
+
+        circuit.set_number("x", usize::MAX);
+
+        let get_input = |name: &str| circuit.inputs[circuit.wires.get(&name.to_string()).unwrap()];
+
+        assert_eq!(Some(1), get_input("x00"));
+        assert_eq!(Some(0), get_input("x64"));
+        assert_eq!(Some(1), get_input("xyz"));
+    }
+
+    #[test]
+    fn test_add_all() {
+        let mut adder = get_circuit(&[], &get_adder_lines(4));
+        adder.set_number("x", 0);
+        adder.set_number("y", 0);
+
+        let netlist = adder.compile().unwrap();
+        let pairs = (0..16).cartesian_product(0..16).collect_vec();
+
+        assert_eq!(
+            pairs.iter().map(|(x, y)| x + y).collect_vec(),
+            netlist.add_all(&pairs)
+        );
+
+        let checks = get_adder_checks(4);
+        assert_eq!(checks.len(), checks.iter().unique().count());
+        assert!(checks.iter().all(|(x, y)| *x < 16 && *y < 16));
+    }
+
+    #[rstest]
+    #[case(&[("z03", "a03")])]
+    #[case(&[("s02", "a02"), ("z05", "c05")])]
+    #[case(&[("z01", "s04"), ("c02", "a06"), ("s05", "a05"), ("z07", "a07")])]
+    fn test_repair_adder(#[case] swaps: &[(&str, &str)]) {
+        let mut adder = get_circuit(&[], &get_adder_lines(8));
+        adder.set_number("x", 0);
+        adder.set_number("y", 0);
+
+        for (first, second) in swaps {
+            let first = adder.wires.get(&first.to_string()).unwrap();
+            let second = adder.wires.get(&second.to_string()).unwrap();
+
+            adder.swap_outputs(first, second);
+        }
+
+        let expected = swaps
+            .iter()
+            .flat_map(|(first, second)| [first.to_string(), second.to_string()])
+            .sorted()
+            .collect_vec();
+
+        assert_eq!(Ok(expected), repair_adder(&mut adder));
+        assert_eq!(
+            None,
+            get_first_wrong_bit(&adder.compile().unwrap(), &get_adder_checks(8))
+        );
+    }
+
+    #[test]
+    fn test_repair_adder_errors() {
+        let mut no_inputs = get_circuit(&[], &["y00 AND y01 -> z00".to_string()]);
+        assert_eq!(
+            Err("There are no x wires, so there's nothing to add.".to_string()),
+            repair_adder(&mut no_inputs)
+        );
+
+        // Bit 6 is only wrong when x02 and y05 differ from x05 and y02, which none of the checks
+        // do, so swapping z03 and a03 back passes them but not the random pairs.
+        let mut lines = get_adder_lines(8)
+            .into_iter()
+            .map(|line| line.replace("-> z06", "-> t06"))
+            .collect_vec();
+        lines.extend([
+            "x02 AND y05 -> u06".to_string(),
+            "x05 AND y02 -> v06".to_string(),
+            "u06 XOR v06 -> w06".to_string(),
+            "t06 XOR w06 -> z06".to_string(),
+        ]);
+
+        let mut adder = get_circuit(&[], &lines);
+        adder.set_number("x", 0);
+        adder.set_number("y", 0);
+
+        let first = adder.wires.get(&"z03".to_string()).unwrap();
+        let second = adder.wires.get(&"a03".to_string()).unwrap();
+        adder.swap_outputs(first, second);
+
+        let gates = adder.gates.clone();
+        let error = repair_adder(&mut adder).unwrap_err();
+        assert!(error.starts_with("Even after swapping"));
+        assert_eq!(gates, adder.gates);
+    }
+
+    #[rstest]
+    #[case(&[], None)]
+    #[case(&[("z03", "a03")], Some((3, "20c Xor 30s -> z03", vec!["30a", "z03"])))]
+    #[case(&[("s02", "a02")], Some((2, "10c Xor 20a -> z02", vec!["20a", "20s"])))]
+    #[case(&[("z05", "c05"), ("s01", "a01")], Some((1, "00c Xor 10a -> z01", vec!["10a", "10s"])))]
+    fn test_compare_to_adder(
+        #[case] swaps: &[(&str, &str)],
+        #[case] expected: Option<(usize, &str, Vec<&str>)>,
+    ) {
+        // Named differently from the standard adder, so only the structure can match.
+        let lines = get_adder_lines(8)
+            .into_iter()
+            .map(|line| {
+                line.split(" ")
+                    .map(|word| match word.chars().next() {
+                        Some('a' | 'b' | 'c' | 's') => word.chars().rev().collect(),
+                        _ => word.to_string(),
+                    })
+                    .join(" ")
+            })
+            .collect_vec();
+
+        let mut adder = get_circuit(&[], &lines);
+        adder.set_number("x", 0);
+        adder.set_number("y", 0);
+
+        for (first, second) in swaps {
+            let rename = |name: &str| match name.starts_with("z") {
+                true => name.to_string(),
+                false => name.chars().rev().collect(),
+            };
+
+            let first = adder.wires.get(&rename(first)).unwrap();
+            let second = adder.wires.get(&rename(second)).unwrap();
+
+            adder.swap_outputs(first, second);
+        }
+
+        let expected = expected.map(|(bit, gate, suspects)| Divergence {
+            bit,
+            expected: gate.to_string(),
+            suspects: suspects.into_iter().map(String::from).collect(),
+        });
+
+        assert_eq!(expected, compare_to_adder(&adder));
+    }
+
+    // Same as before the names were interned, to compare against.
+    fn calculate_output_by_name(
+        known_registers: &HashMap<String, usize>,
+        connected_gates: &ConnectedGates,
+    ) -> usize {
+        let mut values = known_registers.clone();
+
+        while values.len() < known_registers.len() + connected_gates.len() {
+            for (output, (input_1, gate, input_2)) in connected_gates.iter() {
+                if values.contains_key(output) {
+                    continue;
+                }
+
+                if let (Some(value_1), Some(value_2)) = (values.get(input_1), values.get(input_2)) {
+                    values.insert(output.clone(), gate.calculate(*value_1, *value_2));
+                }
+            }
+        }
+
+        values
+            .into_iter()
+            .filter(|(name, _)| name.starts_with("z"))
+            .sorted()
+            .rev()
+            .fold(0, |output, (_, value)| (output << 1) | value)
+    }
+
+    // cargo test --release bench_evaluation -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_evaluation() {
+        use std::time::Instant;
+
+        let rounds = 200;
+        let connected_gates: ConnectedGates = get_adder_lines(45)
+            .into_iter()
+            .map(parse_gate_line)
+            .collect();
+
+        let mut known_registers = HashMap::new();
+        for bit in 0..45 {
+            known_registers.insert(format!("x{bit:0>2}"), bit % 2);
+            known_registers.insert(format!("y{bit:0>2}"), bit % 3 % 2);
+        }
+
+        let start = Instant::now();
+        let mut total = 0;
+        for _ in 0..rounds {
+            total += calculate_output_by_name(&known_registers, &connected_gates);
+        }
+        println!("By name: {:?} ({total})", start.elapsed());
+
+        let circuit = Circuit::new(&known_registers, &connected_gates);
+
+        let start = Instant::now();
+        let mut total = 0;
+        for _ in 0..rounds {
+            total += circuit.calculate_output().unwrap();
+        }
+        println!("By id:   {:?} ({total})", start.elapsed());
     }
 }
@@ -1,18 +1,25 @@
 use itertools::Itertools;
 
 use crate::file_utilities::read_chunks;
+use crate::map_utilities::{Grid, Transform};
 
-fn parse_lines_to_char_vecs(lines: Vec<String>) -> Vec<Vec<char>> {
-    lines
+fn parse_data(file_path: String) -> Vec<Grid<char>> {
+    read_chunks(file_path)
         .into_iter()
-        .map(|line| line.chars().collect_vec())
+        .map(Grid::from_lines)
         .collect_vec()
 }
 
-fn parse_data(file_path: String) -> Vec<Vec<Vec<char>>> {
-    read_chunks(file_path)
-        .into_iter()
-        .map(parse_lines_to_char_vecs)
+// How far each column reaches in from the top row, not counting the top row itself.
+fn get_heights(lock: &Grid<char>) -> Vec<usize> {
+    lock.columns()
+        .map(|column| {
+            column
+                .into_iter()
+                .skip(1)
+                .take_while(|c| **c == '#')
+                .count()
+        })
         .collect_vec()
 }
 
@@ -27,36 +34,25 @@ pub fn run(file_path: String, part: i32) -> i64 {
 
 fn part_1(file_path: String) -> i64 {
     let keys_and_locks = parse_data(file_path);
-    let width = keys_and_locks.first().unwrap()[0].len();
-    let height = keys_and_locks.first().unwrap().len();
+    let width = keys_and_locks.first().unwrap().width;
+    let height = keys_and_locks.first().unwrap().height;
 
     let mut locks = vec![];
     let mut keys = vec![];
 
     for key_or_lock in keys_and_locks.into_iter() {
-        let mut heights = vec![];
-        if key_or_lock[0].iter().all(|c| *c == '#') {
+        if key_or_lock
+            .rows()
+            .next()
+            .unwrap()
+            .iter()
+            .all(|c| **c == '#')
+        {
             // Is lock!
-            for column in 0..width {
-                for (height, row) in key_or_lock.iter().skip(1).enumerate() {
-                    if row[column] == '.' {
-                        heights.push(height);
-                        break;
-                    }
-                }
-            }
-            locks.push(heights);
+            locks.push(get_heights(&key_or_lock));
         } else {
-            // Is key!
-            for column in 0..width {
-                for (height, row) in key_or_lock.iter().rev().skip(1).enumerate() {
-                    if row[column] == '.' {
-                        heights.push(height);
-                        break;
-                    }
-                }
-            }
-            keys.push(heights);
+            // Is key! Upside down, it's just another lock.
+            keys.push(get_heights(&key_or_lock.transform(Transform::FlipVertical)));
         }
     }
 
@@ -67,14 +63,14 @@ fn part_1(file_path: String) -> i64 {
             let mut overlap = false;
             for column in 0..width {
                 if lock[column] + key[column] > height - 2 {
-                    // println!("{lock:?} and {key:?} overlap at column {column}");
+                    trace!("{lock:?} and {key:?} overlap at column {column}");
                     overlap = true;
                     break;
                 }
             }
 
             if !overlap {
-                // println!("{lock:?} and {key:?} do NOT overlap!");
+                trace!("{lock:?} and {key:?} do NOT overlap!");
                 result += 1;
             }
         }
@@ -1,12 +1,10 @@
 use itertools::Itertools;
 
 use crate::file_utilities::read_lines;
+use crate::map_utilities::{Grid, Point, ROTATIONS};
 
-fn parse_data(file_path: String) -> Vec<Vec<char>> {
-    read_lines(file_path)
-        .into_iter()
-        .map(|s| s.chars().collect_vec())
-        .collect::<Vec<_>>()
+fn parse_data(file_path: String) -> Grid<char> {
+    Grid::from_lines(read_lines(file_path))
 }
 
 #[allow(dead_code)]
@@ -18,82 +16,61 @@ pub fn run(file_path: String, part: i32) -> i32 {
     }
 }
 
-fn part_1(file_path: String) -> i32 {
-    let data = parse_data(file_path);
-    let length = data.len();
-
-    let mut count = 0;
-    let neighbours = [
-        (0, 1),
-        (1, 0),
-        (0, -1),
-        (-1, 0),
-        (1, 1),
-        (-1, -1),
-        (1, -1),
-        (-1, 1),
-    ];
-    let target = ['X', 'M', 'A', 'S'];
-
-    for i in 0..length {
-        for j in 0..length {
-            for neighbour in neighbours.iter() {
-                let mut valid = true;
-
-                for (step, target_char) in target.iter().enumerate() {
-                    let current_i = (i as isize) + (step as isize) * neighbour.0;
-                    let current_j = (j as isize) + (step as isize) * neighbour.1;
+// Every rotation gives us one orthogonal and one diagonal reading direction, so we only ever
+// need to read left to right and top-left to bottom-right.
+fn count_words(grid: &Grid<char>, word: &str) -> usize {
+    ROTATIONS
+        .into_iter()
+        .map(|rotation| grid.transform(rotation))
+        .map(|rotated| {
+            rotated
+                .rows()
+                .chain(rotated.diagonals())
+                .map(|line| line.into_iter().collect::<String>().matches(word).count())
+                .sum::<usize>()
+        })
+        .sum()
+}
 
-                    if current_i < 0
-                        || current_i >= (length as isize)
-                        || current_j < 0
-                        || current_j >= (length as isize)
-                    {
-                        valid = false;
-                        break;
-                    }
+fn matches_at(grid: &Grid<char>, pattern: &Grid<char>, top_left: Point) -> bool {
+    pattern.points().all(|point| {
+        pattern[point] == '.'
+            || grid.get(Point::new(
+                top_left.row + point.row,
+                top_left.column + point.column,
+            )) == Some(&pattern[point])
+    })
+}
 
-                    valid = valid && (*target_char == data[current_i as usize][current_j as usize]);
-                }
+// The pattern is a small grid where '.' matches anything, and it's tried in all rotations.
+fn count_pattern(grid: &Grid<char>, pattern: &Grid<char>) -> usize {
+    ROTATIONS
+        .into_iter()
+        .map(|rotation| pattern.transform(rotation))
+        .unique()
+        .map(|rotated| {
+            grid.points()
+                .filter(|top_left| matches_at(grid, &rotated, *top_left))
+                .count()
+        })
+        .sum()
+}
 
-                if valid {
-                    count += 1;
-                }
-            }
-        }
-    }
+fn part_1(file_path: String) -> i32 {
+    let grid = parse_data(file_path);
 
-    count
+    count_words(&grid, "XMAS") as i32
 }
 
 fn part_2(file_path: String) -> i32 {
-    let data = parse_data(file_path);
-    let length = data.len();
-
-    let mut count = 0;
-
-    let targets = ["MMASS", "MSAMS", "SMASM", "SSAMM"];
-
-    for i in 1..length - 1 {
-        for j in 1..length - 1 {
-            let letters = vec![
-                data[i - 1][j - 1],
-                data[i - 1][j + 1],
-                data[i][j],
-                data[i + 1][j - 1],
-                data[i + 1][j + 1],
-            ];
-
-            let string = String::from_iter(letters);
-            let valid = targets.contains(&string.as_str());
-
-            if valid {
-                count += 1;
-            }
-        }
-    }
-
-    count
+    let grid = parse_data(file_path);
+    let pattern = Grid::from_lines(vec![
+        "M.S".to_string(),
+        ".A.".to_string(),
+        "M.S".to_string(),
+    ]);
+
+    count_pattern(&grid, &pattern) as i32
 }
 
 #[cfg(test)]
@@ -115,4 +92,34 @@ mod tests {
     fn test_part_2(#[case] is_test: bool, #[case] expected: i32) {
         assert_eq!(expected, part_2(get_file_path(is_test, 4, None)));
     }
+
+    #[test]
+    fn test_orientations() {
+        let grid = Grid::from_lines(
+            [
+                "MMMSXXMASM",
+                "MSAMXMSMSA",
+                "AMXSXMAAMM",
+                "MSAMASMSMX",
+                "XMASAMXAMM",
+                "XXAMMXXAMA",
+                "SMSMSASXSS",
+                "SAXAMASAAA",
+                "MAMMMXMMMM",
+                "MXMXAXMASX",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        );
+        let pattern = Grid::from_lines(
+            ["M.S", ".A.", "M.S"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        );
+
+        assert_eq!(18, count_words(&grid, "XMAS"));
+        assert_eq!(9, count_pattern(&grid, &pattern));
+    }
 }
@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 use std::collections::HashSet;
 
 use crate::file_utilities::read_two_chunks;
+use crate::graph_utilities::Graph;
 
 fn parse_rule_line(line: String) -> (usize, usize) {
     let values = line
@@ -95,6 +96,19 @@ fn alternative(file_path: String, equal: bool) -> i32 {
         .sum()
 }
 
+// Every rule is an edge from the page that has to come first.
+pub fn export_graph(file_path: String, output_path: String) {
+    let (rules, _) = parse_data(file_path);
+
+    let mut graph = Graph::new_directed();
+
+    for (before, after) in rules {
+        graph.add_edge(before, after);
+    }
+
+    graph.save(output_path);
+}
+
 fn part_1(file_path: String) -> i32 {
     let (rules, page_lists) = parse_data(file_path);
 
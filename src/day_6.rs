@@ -1,8 +1,8 @@
+use crate::animation_utilities::Recorder;
 use crate::file_utilities::read_lines;
-use itertools::Itertools;
-use std::collections::HashSet;
+use crate::map_utilities::{Point, PointMap, PointSet};
+use crate::render_utilities::{Canvas, GREEN, GREY, WHITE, YELLOW};
 use std::hash::Hash;
-use std::iter;
 
 fn parse_data(file_path: String) -> Vec<String> {
     read_lines(file_path)
@@ -67,15 +67,23 @@ fn parse_guard(c: char) -> Direction {
     }
 }
 
-fn get_guard_and_obstacles(map: &[String]) -> ((usize, usize, Direction), HashSet<(usize, usize)>) {
+fn to_point((row, column): (usize, usize)) -> Point {
+    Point::new(row as isize, column as isize)
+}
+
+fn get_direction_bit(direction: Direction) -> u8 {
+    1 << (direction as u8)
+}
+
+fn get_guard_and_obstacles(map: &[String]) -> ((usize, usize, Direction), PointSet) {
     let mut guard = (0, 0, Direction::Default);
-    let mut obstacles = HashSet::new();
+    let mut obstacles = PointSet::new(map[0].len(), map.len());
 
     for (i, row) in map.iter().enumerate() {
         for (j, c) in row.chars().enumerate() {
             match c {
                 '#' => {
-                    obstacles.insert((i, j));
+                    obstacles.insert(to_point((i, j)));
                     continue;
                 }
                 '.' => continue,
@@ -88,21 +96,55 @@ fn get_guard_and_obstacles(map: &[String]) -> ((usize, usize, Direction), HashSe
     (guard, obstacles)
 }
 
+fn get_route_canvas(
+    guard: (usize, usize, Direction),
+    obstacles: &PointSet,
+    history: &PointMap<u8>,
+) -> Canvas {
+    let mut canvas = Canvas::from_points(
+        obstacles.width(),
+        obstacles.height(),
+        obstacles.iter(),
+        GREY,
+        WHITE,
+    );
+
+    canvas.add_points(history.keys(), YELLOW);
+    canvas.add_points([to_point((guard.0, guard.1))], GREEN);
+
+    canvas
+}
+
+// The history holds the directions the guard had on every visited point, as a bit mask.
 fn calculate_route(
     guard: (usize, usize, Direction),
-    obstacles: &HashSet<(usize, usize)>,
+    obstacles: &PointSet,
     size: usize,
-) -> (HashSet<(usize, usize, Direction)>, bool) {
+    mut recorder: Option<&mut Recorder>,
+) -> (PointMap<u8>, bool) {
     let mut guard = guard;
-    let mut history = HashSet::new();
+    let mut history = PointMap::new(size, size);
+    let mut step = 0;
+
+    loop {
+        let directions = history.get_or_insert_with(to_point((guard.0, guard.1)), || 0);
+
+        if *directions & get_direction_bit(guard.2) != 0 {
+            return (history, true); // Loop
+        }
+
+        *directions |= get_direction_bit(guard.2);
+
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(step, || get_route_canvas(guard, obstacles, &history));
+        }
 
-    while !history.contains(&guard) {
-        history.insert(guard);
+        step += 1;
 
         let next = go_straight(guard.0, guard.1, guard.2, size);
 
         if let Some(next) = next {
-            if obstacles.contains(&(next.0, next.1)) {
+            if obstacles.contains(&to_point((next.0, next.1))) {
                 guard = (guard.0, guard.1, turn_right(guard.2));
             } else {
                 guard = next;
@@ -113,46 +155,65 @@ fn calculate_route(
 
         return (history, false); // No loop
     }
+}
+
+pub fn render(file_path: String, output_path: String) {
+    let map = parse_data(file_path);
+    let (guard, obstacles) = get_guard_and_obstacles(&map);
+
+    let (history, _) = calculate_route(guard, &obstacles, map.len(), None);
+
+    get_route_canvas(guard, &obstacles, &history).save(output_path, 8);
+}
+
+pub fn animate(file_path: String, fps: usize) -> Recorder {
+    let map = parse_data(file_path);
+    let (guard, obstacles) = get_guard_and_obstacles(&map);
+
+    let mut recorder = Recorder::new(fps, map.len() / 10);
+    calculate_route(guard, &obstacles, map.len(), Some(&mut recorder));
 
-    (history, true) // Loop
+    recorder
 }
 
 fn part_1(file_path: String) -> i32 {
     let map = parse_data(file_path);
     let (guard, obstacles) = get_guard_and_obstacles(&map);
 
-    let (history, is_loop) = calculate_route(guard, &obstacles, map.len());
+    let (history, is_loop) = calculate_route(guard, &obstacles, map.len(), None);
 
     assert!(!is_loop);
 
-    history.into_iter().map(|g| (g.0, g.1)).unique().count() as i32
+    history.len() as i32
 }
 
 fn part_2(file_path: String) -> i32 {
     let map = parse_data(file_path);
-    let (guard, obstacles) = get_guard_and_obstacles(&map);
+    let (guard, mut obstacles) = get_guard_and_obstacles(&map);
 
-    let (history, _) = calculate_route(guard, &obstacles, map.len());
+    let (history, _) = calculate_route(guard, &obstacles, map.len(), None);
 
+    // Everything but the starting state of the guard.
+    let guard_point = to_point((guard.0, guard.1));
     let relevant_history = history
-        .into_iter()
-        .filter(|g| *g != guard)
-        .map(|g| (g.0, g.1))
-        .unique();
+        .iter()
+        .filter(|(point, directions)| {
+            *point != guard_point || **directions != get_direction_bit(guard.2)
+        })
+        .map(|(point, _)| point)
+        .collect::<Vec<_>>();
 
     let mut result = 0;
 
-    for (i, j) in relevant_history.into_iter() {
-        if obstacles.contains(&(i, j)) {
+    for point in relevant_history.into_iter() {
+        if obstacles.contains(&point) {
             continue; // Negligible, but not point in adding this one.
         }
 
-        let new_obstacles = obstacles
-            .clone()
-            .into_iter()
-            .chain(iter::once((i, j)))
-            .collect::<HashSet<_>>();
-        let (_, is_loop) = calculate_route(guard, &new_obstacles, map.len());
+        // No need to copy all the obstacles, just put the new one in and take it out after.
+        obstacles.insert(point);
+        let (_, is_loop) = calculate_route(guard, &obstacles, map.len(), None);
+        obstacles.remove(&point);
 
         if is_loop {
             result += 1;
@@ -3,6 +3,7 @@ use std::cmp::Ordering;
 use std::collections::VecDeque;
 
 use crate::file_utilities::read_lines;
+use crate::range_utilities::RangeSet;
 
 fn parse_line_to_int(line: String) -> Vec<usize> {
     line.chars()
@@ -165,57 +166,25 @@ fn part_1(file_path: String) -> u64 {
 fn part_2(file_path: String) -> u64 {
     let data = parse_data(file_path);
 
-    let (mut files, mut gaps) = get_files_and_gaps(data);
+    let (mut files, gaps) = get_files_and_gaps(data);
+    let mut free_space = gaps
+        .into_iter()
+        .map(|gap| gap.index..gap.index + gap.size)
+        .collect::<RangeSet>();
 
     // Now start pushing things from the end to the beginning.
-    let mut file_index = files.len() - 1;
-
-    loop {
-        for gap in &mut gaps {
-            let file = files[file_index];
-
-            if gap.index >= file.index {
-                break;
-            }
+    for file in files.iter_mut().rev() {
+        let Some(gap) = free_space.first_fit(file.size) else {
+            continue;
+        };
 
-            match gap.size.cmp(&file.size) {
-                Ordering::Equal => {
-                    // Move entire file into gap, and move to the next file
-                    files[file_index] = File {
-                        index: gap.index,
-                        size: file.size,
-                        id: file.id,
-                    };
-                    *gap = Gap {
-                        index: gap.index,
-                        size: 0,
-                    };
-
-                    break;
-                }
-                Ordering::Greater => {
-                    // Move entire file into gap, move to the next file, and put a leftover gap.
-                    files[file_index] = File {
-                        index: gap.index,
-                        size: file.size,
-                        id: file.id,
-                    };
-                    *gap = Gap {
-                        index: gap.index + file.size,
-                        size: gap.size - file.size,
-                    };
-
-                    break;
-                }
-                Ordering::Less => {}
-            }
-        }
-
-        if file_index == 0 {
-            break;
+        if gap.start >= file.index {
+            continue;
         }
 
-        file_index -= 1;
+        // No need to free up the file's old spot - everything still to move is to the left of it.
+        free_space.remove(gap.start..gap.start + file.size);
+        file.index = gap.start;
     }
 
     let ordered = files.into_iter().sorted_by_key(|v| v.index).collect_vec();
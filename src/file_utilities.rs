@@ -4,10 +4,29 @@ use std::fs::File;
 use std::io::{self, BufRead, Read};
 use std::path::Path;
 
+pub const DEFAULT_YEAR: u32 = 2024;
+
 pub fn get_file_path(is_test: bool, day: u32, suffix: Option<&str>) -> String {
+    get_year_file_path(DEFAULT_YEAR, is_test, day, suffix)
+}
+
+// Inputs from before there were years (data/test/day_1.txt and so on) still count for the
+// default year, as long as there's no file in the year's own folder.
+pub fn get_year_file_path(year: u32, is_test: bool, day: u32, suffix: Option<&str>) -> String {
     let sub_folder = if is_test { "test" } else { "real" };
     let suffix = suffix.unwrap_or("");
-    format!("./data/{sub_folder}/day_{day}{suffix}.txt")
+    let file_path = format!("./data/{year}/{sub_folder}/day_{day}{suffix}.txt");
+
+    let legacy_file_path = format!("./data/{sub_folder}/day_{day}{suffix}.txt");
+
+    if year == DEFAULT_YEAR
+        && !Path::new(&file_path).exists()
+        && Path::new(&legacy_file_path).exists()
+    {
+        return legacy_file_path;
+    }
+
+    file_path
 }
 
 pub fn read_lines<P>(filename: P) -> Vec<String>
@@ -82,3 +101,20 @@ where
 {
     std::fs::read_to_string(filename).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_year_file_path() {
+        assert_eq!(
+            "./data/2023/test/day_5_2.txt",
+            get_year_file_path(2023, true, 5, Some("_2"))
+        );
+        assert_eq!(
+            "./data/2022/real/day_25.txt",
+            get_year_file_path(2022, false, 25, None)
+        );
+    }
+}
@@ -0,0 +1,349 @@
+#![allow(dead_code)]
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::hash::Hash;
+use std::path::Path;
+
+use itertools::Itertools;
+
+// Hands out consecutive ids, so anything keyed by node can live in a plain Vec.
+#[derive(Clone, Debug)]
+pub struct Interner<T> {
+    ids: HashMap<T, usize>,
+    values: Vec<T>,
+}
+
+impl<T> Default for Interner<T> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            values: vec![],
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Interner<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, value: T) -> usize {
+        if let Some(id) = self.ids.get(&value) {
+            return *id;
+        }
+
+        let id = self.values.len();
+        self.ids.insert(value.clone(), id);
+        self.values.push(value);
+
+        id
+    }
+
+    pub fn get(&self, value: &T) -> Option<usize> {
+        self.ids.get(value).copied()
+    }
+
+    pub fn resolve(&self, id: usize) -> &T {
+        &self.values[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &T)> + '_ {
+        self.values.iter().enumerate()
+    }
+}
+
+// Adjacency lists over interned nodes. Undirected edges are stored in both directions.
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    pub is_directed: bool,
+    nodes: Interner<N>,
+    adjacency: Vec<Vec<usize>>,
+    labels: Vec<Option<String>>,
+    groups: Vec<Option<usize>>,
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    fn new(is_directed: bool) -> Self {
+        Self {
+            is_directed,
+            nodes: Interner::new(),
+            adjacency: vec![],
+            labels: vec![],
+            groups: vec![],
+        }
+    }
+
+    pub fn new_directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn new_undirected() -> Self {
+        Self::new(false)
+    }
+
+    pub fn add_node(&mut self, node: N) -> usize {
+        let id = self.nodes.intern(node);
+
+        if id == self.adjacency.len() {
+            self.adjacency.push(vec![]);
+            self.labels.push(None);
+            self.groups.push(None);
+        }
+
+        id
+    }
+
+    // Adding the same edge twice is a no-op.
+    pub fn add_edge(&mut self, from: N, to: N) -> (usize, usize) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+
+        if !self.contains_edge(from, to) {
+            self.adjacency[from].push(to);
+
+            if !self.is_directed && from != to {
+                self.adjacency[to].push(from);
+            }
+        }
+
+        (from, to)
+    }
+
+    pub fn id(&self, node: &N) -> Option<usize> {
+        self.nodes.get(node)
+    }
+
+    pub fn node(&self, id: usize) -> &N {
+        self.nodes.resolve(id)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = usize> {
+        0..self.node_count()
+    }
+
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        &self.adjacency[id]
+    }
+
+    pub fn contains_edge(&self, from: usize, to: usize) -> bool {
+        self.adjacency[from].contains(&to)
+    }
+
+    // Every edge once - undirected ones only from the lower id to the higher one.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(from, targets)| targets.iter().map(move |to| (from, *to)))
+            .filter(|(from, to)| self.is_directed || from <= to)
+    }
+
+    // Shown instead of the node's name when exporting.
+    pub fn set_label(&mut self, id: usize, label: String) {
+        self.labels[id] = Some(label);
+    }
+
+    // Nodes in the same group are drawn together (a cluster in DOT, a data field in GraphML).
+    pub fn set_group(&mut self, id: usize, group: usize) {
+        self.groups[id] = Some(group);
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl<N: Clone + Eq + Hash + Display> Graph<N> {
+    fn get_label(&self, id: usize) -> String {
+        self.labels[id]
+            .clone()
+            .unwrap_or_else(|| self.node(id).to_string())
+    }
+
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if self.is_directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        let get_node_line =
+            |id: usize| format!(r#"n{id} [label="{}"];"#, escape_dot(&self.get_label(id)));
+
+        let mut lines = vec![format!("{keyword} G {{")];
+
+        let grouped = self
+            .node_ids()
+            .filter_map(|id| self.groups[id].map(|group| (group, id)))
+            .into_group_map();
+
+        for group in grouped.keys().sorted() {
+            lines.push(format!("    subgraph cluster_{group} {{"));
+            lines.extend(
+                grouped[group]
+                    .iter()
+                    .map(|id| format!("        {}", get_node_line(*id))),
+            );
+            lines.push("    }".to_string());
+        }
+
+        lines.extend(
+            self.node_ids()
+                .filter(|id| self.groups[*id].is_none())
+                .map(|id| format!("    {}", get_node_line(id))),
+        );
+
+        lines.extend(
+            self.edges()
+                .map(|(from, to)| format!("    n{from} {arrow} n{to};")),
+        );
+
+        lines.push("}".to_string());
+
+        lines.join("\n")
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let edge_default = if self.is_directed {
+            "directed"
+        } else {
+            "undirected"
+        };
+
+        let mut lines = vec![
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#.to_string(),
+            r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#.to_string(),
+            r#"  <key id="group" for="node" attr.name="group" attr.type="int"/>"#.to_string(),
+            format!(r#"  <graph id="G" edgedefault="{edge_default}">"#),
+        ];
+
+        for id in self.node_ids() {
+            lines.push(format!(r#"    <node id="n{id}">"#));
+            lines.push(format!(
+                r#"      <data key="label">{}</data>"#,
+                escape_xml(&self.get_label(id))
+            ));
+
+            if let Some(group) = self.groups[id] {
+                lines.push(format!(r#"      <data key="group">{group}</data>"#));
+            }
+
+            lines.push("    </node>".to_string());
+        }
+
+        for (index, (from, to)) in self.edges().enumerate() {
+            lines.push(format!(
+                r#"    <edge id="e{index}" source="n{from}" target="n{to}"/>"#
+            ));
+        }
+
+        lines.push("  </graph>".to_string());
+        lines.push("</graphml>".to_string());
+
+        lines.join("\n")
+    }
+
+    // The format is picked by the file extension.
+    pub fn save<P>(&self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("dot") | Some("gv") => self.to_dot(),
+            Some("graphml") => self.to_graphml(),
+            _ => panic!("I don't know how to export {path:?}..."),
+        };
+
+        fs::write(path, contents).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::new();
+
+        assert_eq!(0, interner.intern("ka"));
+        assert_eq!(1, interner.intern("co"));
+        assert_eq!(0, interner.intern("ka"));
+
+        assert_eq!(Some(1), interner.get(&"co"));
+        assert_eq!(None, interner.get(&"de"));
+        assert_eq!("co", *interner.resolve(1));
+        assert_eq!(2, interner.len());
+    }
+
+    #[test]
+    fn test_graph() {
+        let mut undirected = Graph::new_undirected();
+        undirected.add_edge("ka", "co");
+        undirected.add_edge("co", "ka");
+        undirected.add_edge("co", "de");
+
+        let co = undirected.id(&"co").unwrap();
+        let ka = undirected.id(&"ka").unwrap();
+
+        assert_eq!(3, undirected.node_count());
+        assert_eq!(2, undirected.edge_count());
+        assert!(undirected.contains_edge(ka, co));
+        assert!(undirected.contains_edge(co, ka));
+        assert_eq!(2, undirected.neighbours(co).len());
+
+        let mut directed = Graph::new_directed();
+        let (first, second) = directed.add_edge(47, 53);
+
+        assert!(directed.contains_edge(first, second));
+        assert!(!directed.contains_edge(second, first));
+    }
+
+    #[test]
+    fn test_export() {
+        let mut graph = Graph::new_directed();
+        let (x, z) = graph.add_edge("x00", "z00");
+        graph.set_label(z, "z00 \"XOR\"".to_string());
+        graph.set_group(x, 0);
+
+        let dot = graph.to_dot();
+
+        assert!(dot.starts_with("digraph G {"));
+        assert!(dot.contains("subgraph cluster_0 {"));
+        assert!(dot.contains(r#"n1 [label="z00 \"XOR\""];"#));
+        assert!(dot.contains("n0 -> n1;"));
+
+        let graphml = graph.to_graphml();
+
+        assert!(graphml.contains(r#"edgedefault="directed""#));
+        assert!(graphml.contains("z00 &quot;XOR&quot;"));
+        assert!(graphml.contains(r#"<edge id="e0" source="n0" target="n1"/>"#));
+    }
+}
@@ -0,0 +1,174 @@
+#![allow(dead_code)]
+#![allow(unused_macros)]
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::RwLock;
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    // No -v at all only shows warnings and errors, every extra v shows one more level.
+    pub fn from_verbosity(verbosity: usize) -> Self {
+        match verbosity {
+            0 => Level::Warn,
+            1 => Level::Info,
+            2 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Level::Error,
+            1 => Level::Warn,
+            2 => Level::Info,
+            3 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+static TRACED_TARGETS: RwLock<Vec<String>> = RwLock::new(vec![]);
+// So the usual case (nothing traced) never has to take the lock.
+static IS_ANYTHING_TRACED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn get_level() -> Level {
+    Level::from_u8(LEVEL.load(Ordering::Relaxed))
+}
+
+// Everything from this target gets logged, whatever the global level is.
+pub fn trace_target(target: &str) {
+    TRACED_TARGETS
+        .write()
+        .unwrap()
+        .push(normalise_target(target));
+
+    IS_ANYTHING_TRACED.store(true, Ordering::Relaxed);
+}
+
+// Targets are module names, so "day17", "day_17" and "Day_17" are all the same thing.
+fn normalise_target(target: &str) -> String {
+    target.replace('_', "").to_lowercase()
+}
+
+// The module the log statement is in, e.g. "day_17" for "Advent2024::day_17".
+pub fn get_target(module_path: &str) -> &str {
+    module_path.rsplit("::").next().unwrap_or(module_path)
+}
+
+// Same as comparing with normalise_target, without allocating on every log statement.
+fn is_traced(traced_targets: &[String], target: &str) -> bool {
+    traced_targets.iter().any(|traced| {
+        traced.chars().eq(target
+            .chars()
+            .filter(|c| *c != '_')
+            .flat_map(char::to_lowercase))
+    })
+}
+
+// Takes the whole module path, since working out the target is only worth it when tracing.
+pub fn is_enabled(level: Level, module_path: &str) -> bool {
+    if level <= get_level() {
+        return true;
+    }
+
+    IS_ANYTHING_TRACED.load(Ordering::Relaxed)
+        && is_traced(&TRACED_TARGETS.read().unwrap(), get_target(module_path))
+}
+
+// Logs go to stderr, so they never get mixed up with the answers.
+pub fn write_log(level: Level, target: &str, arguments: fmt::Arguments) {
+    eprintln!("[{level} {target}] {arguments}");
+}
+
+// The arguments are only formatted when the level is enabled, so these are free when off.
+// The module is #[macro_use], so these work in every module declared after it.
+macro_rules! log {
+    ($level:expr, $($argument:tt)+) => {{
+        if $crate::log_utilities::is_enabled($level, module_path!()) {
+            $crate::log_utilities::write_log(
+                $level,
+                $crate::log_utilities::get_target(module_path!()),
+                format_args!($($argument)+),
+            );
+        }
+    }};
+}
+
+macro_rules! error {
+    ($($argument:tt)+) => { log!($crate::log_utilities::Level::Error, $($argument)+) };
+}
+
+macro_rules! warn {
+    ($($argument:tt)+) => { log!($crate::log_utilities::Level::Warn, $($argument)+) };
+}
+
+macro_rules! info {
+    ($($argument:tt)+) => { log!($crate::log_utilities::Level::Info, $($argument)+) };
+}
+
+macro_rules! debug {
+    ($($argument:tt)+) => { log!($crate::log_utilities::Level::Debug, $($argument)+) };
+}
+
+macro_rules! trace {
+    ($($argument:tt)+) => { log!($crate::log_utilities::Level::Trace, $($argument)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(Level::Warn, Level::from_verbosity(0));
+        assert_eq!(Level::Debug, Level::from_verbosity(2));
+        assert_eq!(Level::Trace, Level::from_verbosity(7));
+
+        assert!(Level::Error < Level::Warn);
+        assert!(Level::Debug < Level::Trace);
+
+        for level in [Level::Error, Level::Info, Level::Trace] {
+            assert_eq!(level, Level::from_u8(level as u8));
+        }
+    }
+
+    #[test]
+    fn test_targets() {
+        assert_eq!("day_17", get_target("Advent2024::day_17"));
+        assert_eq!("main", get_target("main"));
+
+        let traced = vec![normalise_target("day17")];
+
+        assert!(is_traced(&traced, "day_17"));
+        assert!(is_traced(&traced, "Day17"));
+        assert!(!is_traced(&traced, "day_1"));
+        assert!(!is_traced(&[], "day_17"));
+    }
+}
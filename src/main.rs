@@ -1,24 +1,206 @@
+#[macro_use]
+mod log_utilities;
+
+mod allocation_utilities;
+mod animation_utilities;
+mod cli_utilities;
 mod file_utilities;
+mod graph_utilities;
+
+mod day_1;
+mod day_2;
+mod day_3;
+mod day_4;
+mod day_5;
+mod day_6;
+mod day_7;
+mod day_8;
+mod day_9;
 
+mod day_10;
+mod day_11;
+mod day_12;
+mod day_13;
+mod day_14;
+mod day_15;
+mod day_16;
+mod day_17;
+mod day_18;
+mod day_19;
+mod day_20;
+mod day_21;
+mod day_22;
+mod day_23;
+mod day_24;
 mod day_25;
 mod map_utilities;
+mod random_utilities;
+mod range_utilities;
+mod render_utilities;
+mod repl_utilities;
+mod search_utilities;
 
+use std::env;
 use std::time::Instant;
 
-use crate::day_25::run;
-use crate::file_utilities::get_file_path;
+use crate::allocation_utilities::CountingAllocator;
+use crate::animation_utilities::Recorder;
+use crate::cli_utilities::parse_arguments;
+use crate::file_utilities::get_year_file_path;
+use crate::log_utilities::Level;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Every day answers with its own type, so they all get formatted the same way here.
+fn run(year: u32, day: u32, file_path: String, part: i32) -> String {
+    match (year, day) {
+        (2024, 1) => format!("{:?}", day_1::run(file_path, part)),
+        (2024, 2) => format!("{:?}", day_2::run(file_path, part)),
+        (2024, 3) => format!("{:?}", day_3::run(file_path, part)),
+        (2024, 4) => format!("{:?}", day_4::run(file_path, part)),
+        (2024, 5) => format!("{:?}", day_5::run(file_path, part)),
+        (2024, 6) => format!("{:?}", day_6::run(file_path, part)),
+        (2024, 7) => format!("{:?}", day_7::run(file_path, part)),
+        (2024, 8) => format!("{:?}", day_8::run(file_path, part)),
+        (2024, 9) => format!("{:?}", day_9::run(file_path, part)),
+        (2024, 10) => format!("{:?}", day_10::run(file_path, part)),
+        (2024, 11) => format!("{:?}", day_11::run(file_path, part)),
+        (2024, 12) => format!("{:?}", day_12::run(file_path, part)),
+        (2024, 13) => format!("{:?}", day_13::run(file_path, part)),
+        (2024, 14) => format!("{:?}", day_14::run(file_path, part)),
+        (2024, 15) => format!("{:?}", day_15::run(file_path, part)),
+        (2024, 16) => format!("{:?}", day_16::run(file_path, part)),
+        (2024, 17) => format!("{:?}", day_17::run(file_path, part)),
+        (2024, 18) => format!("{:?}", day_18::run(file_path, part)),
+        (2024, 19) => format!("{:?}", day_19::run(file_path, part)),
+        (2024, 20) => format!("{:?}", day_20::run(file_path, part)),
+        (2024, 21) => format!("{:?}", day_21::run(file_path, part)),
+        (2024, 22) => format!("{:?}", day_22::run(file_path, part)),
+        (2024, 23) => format!("{:?}", day_23::run(file_path, part)),
+        (2024, 24) => format!("{:?}", day_24::run(file_path, part)),
+        (2024, 25) => format!("{:?}", day_25::run(file_path, part)),
+        _ => panic!("There's no day {day} in {year}..."),
+    }
+}
+
+// Only the days with something worth poking at have a REPL.
+fn repl(year: u32, day: u32, file_path: String) {
+    match (year, day) {
+        (2024, 14) => day_14::repl(file_path),
+        (2024, 15) => day_15::repl(file_path),
+        (2024, 17) => day_17::repl(file_path),
+        (2024, 18) => day_18::repl(file_path),
+        (2024, 24) => day_24::repl(file_path),
+        _ => panic!("Day {day} of {year} doesn't have a REPL..."),
+    }
+}
+
+// Only the days with a picture worth looking at can be rendered, as .ppm, .pgm or .svg.
+fn render(year: u32, day: u32, file_path: String, output_path: String) {
+    match (year, day) {
+        (2024, 6) => day_6::render(file_path, output_path),
+        (2024, 14) => day_14::render(file_path, output_path),
+        (2024, 15) => day_15::render(file_path, output_path),
+        (2024, 16) => day_16::render(file_path, output_path),
+        (2024, 20) => day_20::render(file_path, output_path),
+        _ => panic!("Day {day} of {year} can't be rendered..."),
+    }
+}
+
+// Only the days that are really graphs can be exported, as .dot or .graphml.
+fn export_graph(year: u32, day: u32, file_path: String, output_path: String) {
+    match (year, day) {
+        (2024, 5) => day_5::export_graph(file_path, output_path),
+        (2024, 23) => day_23::export_graph(file_path, output_path),
+        (2024, 24) => day_24::export_graph(file_path, output_path),
+        _ => panic!("Day {day} of {year} can't be exported..."),
+    }
+}
+
+// The simulations, recorded a frame every so often.
+fn animate(year: u32, day: u32, file_path: String, fps: usize) -> Recorder {
+    match (year, day) {
+        (2024, 6) => day_6::animate(file_path, fps),
+        (2024, 14) => day_14::animate(file_path, fps),
+        (2024, 15) => day_15::animate(file_path, fps),
+        _ => panic!("Day {day} of {year} can't be animated..."),
+    }
+}
 
 fn main() {
-    let day = 25;
-    let is_test = false;
+    let arguments = parse_arguments(env::args().skip(1));
+
+    log_utilities::set_level(Level::from_verbosity(arguments.verbosity));
+
+    for target in arguments.traced_targets.iter() {
+        log_utilities::trace_target(target);
+    }
+
+    let year = arguments.year;
+    let day = arguments.day;
+    let is_test = arguments.is_test;
+
+    if arguments.is_counting_allocations {
+        allocation_utilities::enable();
+    }
+
+    if arguments.is_repl {
+        repl(year, day, get_year_file_path(year, is_test, day, None));
+        return;
+    }
+
+    if let Some(output_path) = arguments.render_path {
+        render(
+            year,
+            day,
+            get_year_file_path(year, is_test, day, None),
+            output_path,
+        );
+        return;
+    }
+
+    if let Some(output_path) = arguments.export_path {
+        export_graph(
+            year,
+            day,
+            get_year_file_path(year, is_test, day, None),
+            output_path,
+        );
+        return;
+    }
+
+    if arguments.animation_path.is_some() || arguments.is_playing {
+        let file_path = get_year_file_path(year, is_test, day, None);
+        let recorder = animate(year, day, file_path, arguments.fps);
+
+        if let Some(output_path) = arguments.animation_path {
+            recorder.save(output_path, 4);
+        }
+
+        if arguments.is_playing {
+            recorder.play_in_terminal();
+        }
+
+        return;
+    }
 
     for part in [1, 2] {
+        let file_path = get_year_file_path(year, is_test, day, None);
+
+        allocation_utilities::reset();
+
         let start = Instant::now();
-        let result = run(get_file_path(is_test, day, None), part);
+        let result = run(year, day, file_path, part);
         let end = Instant::now();
 
         let duration = end - start;
 
-        println!("Day {day} Part {part}: {result:?}, in {duration:?}.");
+        if allocation_utilities::is_enabled() {
+            let stats = allocation_utilities::get_stats();
+            println!("{year} Day {day} Part {part}: {result}, in {duration:?}, with {stats}.");
+        } else {
+            println!("{year} Day {day} Part {part}: {result}, in {duration:?}.");
+        }
     }
 }
@@ -1,4 +1,7 @@
 #![allow(dead_code)]
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::{Index, IndexMut};
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Direction {
@@ -85,19 +88,11 @@ impl Point {
     }
 
     pub fn neighbour(&self, direction: Direction, map_size: usize) -> Option<Self> {
-        let map_size = map_size as isize;
-        let neighbour = self.unbound_neighbour(direction);
-
-        // TODO: use is_in_bounds for this.
-        if neighbour.row < 0
-            || neighbour.row >= map_size
-            || neighbour.column < 0
-            || neighbour.column >= map_size
-        {
-            return None;
-        }
+        self.neighbour_in(direction, &Bounds::square(map_size))
+    }
 
-        Some(neighbour)
+    pub fn neighbour_in(&self, direction: Direction, bounds: &Bounds) -> Option<Self> {
+        bounds.wrap(self.unbound_neighbour(direction))
     }
 
     pub fn is_in_bounds(&self, map_size: usize) -> bool {
@@ -110,3 +105,1236 @@ impl Point {
         (self.row - other.row).unsigned_abs() + (self.column - other.column).unsigned_abs()
     }
 }
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Topology {
+    // Falling off an edge means leaving the map.
+    Bounded,
+    // Left and right edges are glued together, top and bottom are still walls.
+    Cylinder,
+    // Everything wraps around.
+    Torus,
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bounds {
+    pub width: usize,
+    pub height: usize,
+    pub topology: Topology,
+}
+
+impl Bounds {
+    pub fn new(width: usize, height: usize, topology: Topology) -> Self {
+        Self {
+            width,
+            height,
+            topology,
+        }
+    }
+
+    pub fn square(size: usize) -> Self {
+        Self::new(size, size, Topology::Bounded)
+    }
+
+    fn wraps_rows(&self) -> bool {
+        self.topology == Topology::Torus
+    }
+
+    fn wraps_columns(&self) -> bool {
+        self.topology != Topology::Bounded
+    }
+
+    // Where the point actually ends up on the map, if anywhere.
+    pub fn wrap(&self, point: Point) -> Option<Point> {
+        let (height, width) = (self.height as isize, self.width as isize);
+
+        let row = if self.wraps_rows() {
+            point.row.rem_euclid(height)
+        } else {
+            point.row
+        };
+
+        let column = if self.wraps_columns() {
+            point.column.rem_euclid(width)
+        } else {
+            point.column
+        };
+
+        if row < 0 || row >= height || column < 0 || column >= width {
+            return None;
+        }
+
+        Some(Point::new(row, column))
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.wrap(*point) == Some(*point)
+    }
+
+    // Manhattan distance, going around the edges whenever that's shorter.
+    pub fn distance(&self, first: &Point, second: &Point) -> usize {
+        let get_delta = |delta: isize, size: usize, wraps: bool| {
+            let delta = delta.unsigned_abs();
+
+            if wraps {
+                let delta = delta % size;
+                delta.min(size - delta)
+            } else {
+                delta
+            }
+        };
+
+        get_delta(first.row - second.row, self.height, self.wraps_rows())
+            + get_delta(
+                first.column - second.column,
+                self.width,
+                self.wraps_columns(),
+            )
+    }
+}
+
+// The eight ways to lay a rectangle back down on itself.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Transform {
+    Identity,
+    RotateClockwise,
+    Rotate180,
+    RotateCounterClockwise,
+    // Left and right swap places.
+    FlipHorizontal,
+    // Top and bottom swap places.
+    FlipVertical,
+    // Mirrored along the main (top-left to bottom-right) diagonal.
+    Transpose,
+    // Mirrored along the other diagonal.
+    AntiTranspose,
+}
+
+pub const ROTATIONS: [Transform; 4] = [
+    Transform::Identity,
+    Transform::RotateClockwise,
+    Transform::Rotate180,
+    Transform::RotateCounterClockwise,
+];
+
+pub const TRANSFORMS: [Transform; 8] = [
+    Transform::Identity,
+    Transform::RotateClockwise,
+    Transform::Rotate180,
+    Transform::RotateCounterClockwise,
+    Transform::FlipHorizontal,
+    Transform::FlipVertical,
+    Transform::Transpose,
+    Transform::AntiTranspose,
+];
+
+impl Transform {
+    pub fn inverse(&self) -> Self {
+        match self {
+            Transform::RotateClockwise => Transform::RotateCounterClockwise,
+            Transform::RotateCounterClockwise => Transform::RotateClockwise,
+            other => *other,
+        }
+    }
+
+    // Width and height of a width x height rectangle after the transform.
+    pub fn get_size(&self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Transform::RotateClockwise
+            | Transform::RotateCounterClockwise
+            | Transform::Transpose
+            | Transform::AntiTranspose => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    // Where a point of a width x height rectangle ends up after the transform.
+    pub fn apply(&self, point: Point, width: usize, height: usize) -> Point {
+        let (last_row, last_column) = (height as isize - 1, width as isize - 1);
+        let (row, column) = (point.row, point.column);
+
+        match self {
+            Transform::Identity => point,
+            Transform::RotateClockwise => Point::new(column, last_row - row),
+            Transform::Rotate180 => Point::new(last_row - row, last_column - column),
+            Transform::RotateCounterClockwise => Point::new(last_column - column, row),
+            Transform::FlipHorizontal => Point::new(row, last_column - column),
+            Transform::FlipVertical => Point::new(last_row - row, column),
+            Transform::Transpose => Point::new(column, row),
+            Transform::AntiTranspose => Point::new(last_column - column, last_row - row),
+        }
+    }
+}
+
+// Anything that has neighbours - squares, hexes, voxels - so searches and fills can work on all of them.
+pub trait Neighbourhood: Copy + Eq + Hash {
+    // Neighbours sharing an edge (or a face, in 3D).
+    fn neighbours(&self) -> Vec<Self>;
+
+    // Neighbours sharing anything at all, corners included.
+    fn all_neighbours(&self) -> Vec<Self> {
+        self.neighbours()
+    }
+}
+
+impl Neighbourhood for Point {
+    fn neighbours(&self) -> Vec<Self> {
+        DIRECTIONS
+            .into_iter()
+            .map(|direction| self.unbound_neighbour(direction))
+            .collect()
+    }
+
+    fn all_neighbours(&self) -> Vec<Self> {
+        (-1..=1)
+            .flat_map(|row| (-1..=1).map(move |column| (row, column)))
+            .filter(|delta| *delta != (0, 0))
+            .map(|(row, column)| Self::new(self.row + row, self.column + column))
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+pub const HEX_DIRECTIONS: [HexDirection; 6] = [
+    HexDirection::East,
+    HexDirection::NorthEast,
+    HexDirection::NorthWest,
+    HexDirection::West,
+    HexDirection::SouthWest,
+    HexDirection::SouthEast,
+];
+
+// Axial coordinates for pointy-top hexes - the third cube coordinate is implied by q + r + s = 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl Hex {
+    pub fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    pub fn from_cube(q: isize, r: isize, s: isize) -> Self {
+        assert_eq!(0, q + r + s, "Cube coordinates have to sum to 0!");
+        Self::new(q, r)
+    }
+
+    pub fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn to_cube(self) -> (isize, isize, isize) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn neighbour(&self, direction: HexDirection) -> Self {
+        let (q, r) = match direction {
+            HexDirection::East => (self.q + 1, self.r),
+            HexDirection::NorthEast => (self.q + 1, self.r - 1),
+            HexDirection::NorthWest => (self.q, self.r - 1),
+            HexDirection::West => (self.q - 1, self.r),
+            HexDirection::SouthWest => (self.q - 1, self.r + 1),
+            HexDirection::SouthEast => (self.q, self.r + 1),
+        };
+
+        Self::new(q, r)
+    }
+
+    pub fn distance(&self, other: &Self) -> usize {
+        ((self.q - other.q).unsigned_abs()
+            + (self.r - other.r).unsigned_abs()
+            + (self.s() - other.s()).unsigned_abs())
+            / 2
+    }
+}
+
+impl Neighbourhood for Hex {
+    fn neighbours(&self) -> Vec<Self> {
+        HEX_DIRECTIONS
+            .into_iter()
+            .map(|direction| self.neighbour(direction))
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Voxel {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Voxel {
+    pub fn new(x: isize, y: isize, z: isize) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        (self.x - other.x).unsigned_abs()
+            + (self.y - other.y).unsigned_abs()
+            + (self.z - other.z).unsigned_abs()
+    }
+
+    fn get_offsets(&self, max_total: usize) -> Vec<Self> {
+        let mut result = vec![];
+
+        for x in -1..=1_isize {
+            for y in -1..=1_isize {
+                for z in -1..=1_isize {
+                    let total = x.unsigned_abs() + y.unsigned_abs() + z.unsigned_abs();
+
+                    if total == 0 || total > max_total {
+                        continue;
+                    }
+
+                    result.push(Self::new(self.x + x, self.y + y, self.z + z));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl Neighbourhood for Voxel {
+    // The 6 voxels sharing a face.
+    fn neighbours(&self) -> Vec<Self> {
+        self.get_offsets(1)
+    }
+
+    // All 26 voxels around this one.
+    fn all_neighbours(&self) -> Vec<Self> {
+        self.get_offsets(3)
+    }
+}
+
+// Everything reachable from start through included coordinates. The neighbour function is usually
+// Neighbourhood::neighbours or Neighbourhood::all_neighbours, but can be anything.
+pub fn flood_fill<C, FN, FI>(start: C, get_neighbours: FN, include: FI) -> HashSet<C>
+where
+    C: Copy + Eq + Hash,
+    FN: Fn(&C) -> Vec<C>,
+    FI: Fn(&C) -> bool,
+{
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    let mut explored = HashSet::new();
+
+    while let Some(coordinate) = queue.pop_front() {
+        if explored.contains(&coordinate) || !include(&coordinate) {
+            continue;
+        }
+
+        explored.insert(coordinate);
+        queue.extend(get_neighbours(&coordinate));
+    }
+
+    explored
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "Grid size doesn't match its cells!"
+        );
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+
+        Self::new(width, height, rows.into_iter().flatten().collect())
+    }
+
+    pub fn is_in_bounds(&self, point: Point) -> bool {
+        point.row >= 0
+            && point.row < self.height as isize
+            && point.column >= 0
+            && point.column < self.width as isize
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if !self.is_in_bounds(point) {
+            return None;
+        }
+
+        Some(&self.cells[point.row() * self.width + point.column()])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if !self.is_in_bounds(point) {
+            return None;
+        }
+
+        Some(&mut self.cells[point.row() * self.width + point.column()])
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height).flat_map(move |row| {
+            (0..self.width).map(move |column| Point::new(row as isize, column as isize))
+        })
+    }
+
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS
+            .into_iter()
+            .map(move |direction| point.unbound_neighbour(direction))
+            .filter(|neighbour| self.is_in_bounds(*neighbour))
+    }
+
+    pub fn flood_fill<F>(&self, start: Point, include: F) -> Region
+    where
+        F: Fn(&T) -> bool,
+    {
+        Region::new(flood_fill(start, Point::neighbours, |point| {
+            self.get(*point).is_some_and(&include)
+        }))
+    }
+
+    // Labels every cell with the id of its connected component, where two neighbouring cells are
+    // in the same component if `connected` says so. Returns the labels and the number of components.
+    pub fn label_components<F>(&self, connected: F) -> (Grid<usize>, usize)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let mut labels: Vec<Option<usize>> = vec![None; self.cells.len()];
+        let mut component_count = 0;
+
+        for start in self.points() {
+            let start_index = start.row() * self.width + start.column();
+
+            if labels[start_index].is_some() {
+                continue;
+            }
+
+            let mut queue = VecDeque::new();
+            queue.push_back(start);
+            labels[start_index] = Some(component_count);
+
+            while let Some(point) = queue.pop_front() {
+                for neighbour in self.neighbours(point) {
+                    let neighbour_index = neighbour.row() * self.width + neighbour.column();
+
+                    if labels[neighbour_index].is_some() {
+                        continue;
+                    }
+
+                    if connected(&self[point], &self[neighbour]) {
+                        labels[neighbour_index] = Some(component_count);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            component_count += 1;
+        }
+
+        let labels = labels.into_iter().map(Option::unwrap).collect();
+
+        (Grid::new(self.width, self.height, labels), component_count)
+    }
+
+    pub fn components<F>(&self, connected: F) -> Vec<Region>
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let (labels, component_count) = self.label_components(connected);
+
+        let mut components = vec![HashSet::new(); component_count];
+
+        for point in labels.points() {
+            components[labels[point]].insert(point);
+        }
+
+        components.into_iter().map(Region::new).collect()
+    }
+
+    pub fn transform(&self, transform: Transform) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = transform.get_size(self.width, self.height);
+        let inverse = transform.inverse();
+
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |column| (row, column)))
+            .map(|(row, column)| Point::new(row as isize, column as isize))
+            .map(|point| self[inverse.apply(point, width, height)].clone())
+            .collect();
+
+        Self::new(width, height, cells)
+    }
+
+    fn get_line(&self, start: Point, step: (isize, isize)) -> Vec<&T> {
+        std::iter::successors(Some(start), |point| {
+            Some(Point::new(point.row + step.0, point.column + step.1))
+        })
+        .map_while(|point| self.get(point))
+        .collect()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Vec<&T>> + '_ {
+        (0..self.height).map(|row| self.get_line(Point::new(row as isize, 0), (0, 1)))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = Vec<&T>> + '_ {
+        (0..self.width).map(|column| self.get_line(Point::new(0, column as isize), (1, 0)))
+    }
+
+    // Top-left to bottom-right, starting from the bottom-left corner and ending at the top-right one.
+    pub fn diagonals(&self) -> impl Iterator<Item = Vec<&T>> + '_ {
+        let starts = (0..self.height)
+            .rev()
+            .map(|row| Point::new(row as isize, 0))
+            .chain((1..self.width).map(|column| Point::new(0, column as isize)));
+
+        starts.map(|start| self.get_line(start, (1, 1)))
+    }
+
+    // Top-right to bottom-left, starting from the top-left corner and ending at the bottom-right one.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Vec<&T>> + '_ {
+        let last_column = self.width as isize - 1;
+
+        let starts = (0..self.width)
+            .map(|column| Point::new(0, column as isize))
+            .chain((1..self.height).map(move |row| Point::new(row as isize, last_column)));
+
+        starts.map(|start| self.get_line(start, (1, -1)))
+    }
+}
+
+impl Grid<char> {
+    pub fn from_lines(lines: Vec<String>) -> Self {
+        Self::from_rows(
+            lines
+                .into_iter()
+                .map(|line| line.chars().collect())
+                .collect(),
+        )
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is out of bounds!"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is out of bounds!"))
+    }
+}
+
+// A set of points, with no assumptions on it being inside any particular grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub points: HashSet<Point>,
+}
+
+impl Region {
+    pub fn new(points: HashSet<Point>) -> Self {
+        Self { points }
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.points.contains(point)
+    }
+
+    pub fn area(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn perimeter(&self) -> usize {
+        self.points
+            .iter()
+            .flat_map(|point| DIRECTIONS.map(|direction| point.unbound_neighbour(direction)))
+            .filter(|neighbour| !self.contains(neighbour))
+            .count()
+    }
+
+    // A polygon has as many sides as it has corners, so count those instead.
+    pub fn sides(&self) -> usize {
+        let mut corners = 0;
+
+        for point in self.points.iter() {
+            for direction in DIRECTIONS {
+                let other_direction = direction.turn_right();
+
+                let first = self.contains(&point.unbound_neighbour(direction));
+                let second = self.contains(&point.unbound_neighbour(other_direction));
+                let diagonal = self.contains(
+                    &point
+                        .unbound_neighbour(direction)
+                        .unbound_neighbour(other_direction),
+                );
+
+                let is_outer_corner = !first && !second;
+                let is_inner_corner = first && second && !diagonal;
+
+                if is_outer_corner || is_inner_corner {
+                    corners += 1;
+                }
+            }
+        }
+
+        corners
+    }
+
+    // Top-left and bottom-right corners, inclusive.
+    pub fn bounding_box(&self) -> Option<(Point, Point)> {
+        let min_row = self.points.iter().map(|point| point.row).min()?;
+        let max_row = self.points.iter().map(|point| point.row).max()?;
+        let min_column = self.points.iter().map(|point| point.column).min()?;
+        let max_column = self.points.iter().map(|point| point.column).max()?;
+
+        Some((
+            Point::new(min_row, min_column),
+            Point::new(max_row, max_column),
+        ))
+    }
+
+    // Connected groups of points outside the region that can't reach the outside world.
+    pub fn holes(&self) -> Vec<Region> {
+        let Some((top_left, bottom_right)) = self.bounding_box() else {
+            return vec![];
+        };
+
+        // Anything in the bounding box (with a margin of one) that isn't part of the region.
+        let width = (bottom_right.column - top_left.column + 3) as usize;
+        let height = (bottom_right.row - top_left.row + 3) as usize;
+        let origin = Point::new(top_left.row - 1, top_left.column - 1);
+
+        let outside = Grid::new(
+            width,
+            height,
+            (0..height as isize)
+                .flat_map(|row| (0..width as isize).map(move |column| (row, column)))
+                .map(|(row, column)| {
+                    !self.contains(&Point::new(origin.row + row, origin.column + column))
+                })
+                .collect(),
+        );
+
+        let reachable = outside.flood_fill(Point::new(0, 0), |is_outside| *is_outside);
+
+        outside
+            .components(|first, second| *first && *second)
+            .into_iter()
+            .filter(|component| {
+                component
+                    .points
+                    .iter()
+                    .all(|point| outside[*point] && !reachable.contains(point))
+            })
+            .map(|component| {
+                Region::new(
+                    component
+                        .points
+                        .into_iter()
+                        .map(|point| {
+                            Point::new(point.row + origin.row, point.column + origin.column)
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn encloses(&self, other: &Region) -> bool {
+        let holes = self.holes();
+
+        !other.points.is_empty()
+            && other
+                .points
+                .iter()
+                .all(|point| holes.iter().any(|hole| hole.contains(point)))
+    }
+}
+
+// Everything below is keyed by the linear index of a point inside a fixed width x height
+// rectangle, which beats hashing in the hot loops. Points outside the rectangle are never
+// contained, and inserting them panics.
+fn get_linear_index(width: usize, height: usize, point: Point) -> Option<usize> {
+    if point.row < 0
+        || point.row >= height as isize
+        || point.column < 0
+        || point.column >= width as isize
+    {
+        return None;
+    }
+
+    Some(point.row() * width + point.column())
+}
+
+fn get_point(width: usize, index: usize) -> Point {
+    Point::new((index / width) as isize, (index % width) as isize)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGrid {
+    pub width: usize,
+    pub height: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            words: vec![0; (width * height).div_ceil(64)],
+        }
+    }
+
+    pub fn get(&self, point: Point) -> bool {
+        match get_linear_index(self.width, self.height, point) {
+            Some(index) => self.words[index / 64] & (1 << (index % 64)) != 0,
+            None => false,
+        }
+    }
+
+    // Returns the previous value.
+    pub fn set(&mut self, point: Point, value: bool) -> bool {
+        let index = get_linear_index(self.width, self.height, point)
+            .unwrap_or_else(|| panic!("{point:?} is out of bounds!"));
+
+        let word = &mut self.words[index / 64];
+        let mask = 1 << (index % 64);
+        let previous = *word & mask != 0;
+
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+
+        previous
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, word)| {
+                let mut word = *word;
+
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+
+                    Some(word_index * 64 + bit)
+                })
+            })
+            .map(|index| get_point(self.width, index))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointSet {
+    bits: BitGrid,
+    len: usize,
+}
+
+impl PointSet {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bits: BitGrid::new(width, height),
+            len: 0,
+        }
+    }
+
+    pub fn from_points<I>(width: usize, height: usize, points: I) -> Self
+    where
+        I: IntoIterator<Item = Point>,
+    {
+        let mut set = Self::new(width, height);
+        set.extend(points);
+        set
+    }
+
+    pub fn width(&self) -> usize {
+        self.bits.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.bits.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.bits.get(*point)
+    }
+
+    // Same as HashSet - true if the point wasn't there yet.
+    pub fn insert(&mut self, point: Point) -> bool {
+        let was_there = self.bits.set(point, true);
+
+        if !was_there {
+            self.len += 1;
+        }
+
+        !was_there
+    }
+
+    pub fn remove(&mut self, point: &Point) -> bool {
+        if !self.contains(point) {
+            return false;
+        }
+
+        self.bits.set(*point, false);
+        self.len -= 1;
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.bits.iter()
+    }
+
+    pub fn transform(&self, transform: Transform) -> Self {
+        let (width, height) = transform.get_size(self.width(), self.height());
+
+        Self::from_points(
+            width,
+            height,
+            self.iter()
+                .map(|point| transform.apply(point, self.width(), self.height())),
+        )
+    }
+}
+
+impl Extend<Point> for PointSet {
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, points: I) {
+        for point in points {
+            self.insert(point);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointMap<T> {
+    pub width: usize,
+    pub height: usize,
+    values: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> PointMap<T> {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            values: (0..width * height).map(|_| None).collect(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains_key(&self, point: &Point) -> bool {
+        self.get(point).is_some()
+    }
+
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        let index = get_linear_index(self.width, self.height, *point)?;
+        self.values[index].as_ref()
+    }
+
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        let index = get_linear_index(self.width, self.height, *point)?;
+        self.values[index].as_mut()
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        let index = get_linear_index(self.width, self.height, point)
+            .unwrap_or_else(|| panic!("{point:?} is out of bounds!"));
+
+        let previous = self.values[index].replace(value);
+
+        if previous.is_none() {
+            self.len += 1;
+        }
+
+        previous
+    }
+
+    pub fn remove(&mut self, point: &Point) -> Option<T> {
+        let index = get_linear_index(self.width, self.height, *point)?;
+        let previous = self.values[index].take();
+
+        if previous.is_some() {
+            self.len -= 1;
+        }
+
+        previous
+    }
+
+    // Like HashMap::entry(...).or_insert_with(...), minus the Entry type.
+    pub fn get_or_insert_with<F>(&mut self, point: Point, default: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        if !self.contains_key(&point) {
+            self.insert(point, default());
+        }
+
+        self.get_mut(&point).unwrap()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.values.iter().enumerate().filter_map(|(index, value)| {
+            value
+                .as_ref()
+                .map(|value| (get_point(self.width, index), value))
+        })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = Point> + '_ {
+        self.iter().map(|(point, _)| point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_garden() -> Grid<char> {
+        Grid::from_lines(
+            ["OOOOO", "OXOXO", "OOOOO", "OXOXO", "OOOOO"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_components() {
+        let garden = get_garden();
+        let regions = garden.components(|first, second| first == second);
+
+        assert_eq!(5, regions.len());
+
+        let outer = regions.iter().find(|region| region.area() == 21).unwrap();
+        assert_eq!(36, outer.perimeter());
+        assert_eq!(20, outer.sides());
+        assert_eq!(4, outer.holes().len());
+
+        let inner = regions.iter().find(|region| region.area() == 1).unwrap();
+        assert_eq!(4, inner.perimeter());
+        assert_eq!(4, inner.sides());
+        assert!(inner.holes().is_empty());
+        assert!(outer.encloses(inner));
+        assert!(!inner.encloses(outer));
+    }
+
+    #[test]
+    fn test_point_set() {
+        let mut set = PointSet::new(70, 3);
+
+        assert!(set.insert(Point::new(2, 69)));
+        assert!(set.insert(Point::new(0, 1)));
+        assert!(!set.insert(Point::new(0, 1)));
+        assert!(!set.contains(&Point::new(-1, 1)));
+        assert_eq!(2, set.len());
+
+        assert_eq!(
+            vec![Point::new(0, 1), Point::new(2, 69)],
+            set.iter().collect::<Vec<_>>()
+        );
+
+        assert!(set.remove(&Point::new(0, 1)));
+        assert!(!set.remove(&Point::new(0, 1)));
+        assert_eq!(1, set.len());
+    }
+
+    #[test]
+    fn test_point_map() {
+        let mut map = PointMap::new(4, 4);
+
+        assert_eq!(None, map.insert(Point::new(1, 2), 'a'));
+        assert_eq!(Some('a'), map.insert(Point::new(1, 2), 'b'));
+        *map.get_or_insert_with(Point::new(3, 3), || 'c') = 'd';
+
+        assert_eq!(Some(&'d'), map.get(&Point::new(3, 3)));
+        assert_eq!(None, map.get(&Point::new(4, 3)));
+        assert_eq!(2, map.len());
+        assert_eq!(
+            vec![Point::new(1, 2), Point::new(3, 3)],
+            map.keys().collect::<Vec<_>>()
+        );
+    }
+
+    // cargo test --release bench_point_sets -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_point_sets() {
+        use rustc_hash::FxHashSet;
+        use std::time::Instant;
+
+        let size = 141;
+        let rounds = 200;
+
+        // A deterministic scatter of points, so all the sets do exactly the same work.
+        let points = (0..size * size)
+            .map(|index| (index * 7919) % (size * size))
+            .map(|index| Point::new((index / size) as isize, (index % size) as isize))
+            .collect::<Vec<_>>();
+
+        let start = Instant::now();
+        let mut found = 0;
+        for _ in 0..rounds {
+            let mut set = HashSet::new();
+            for point in points.iter() {
+                set.insert(*point);
+                found += set.contains(&point.unbound_neighbour(Direction::Up)) as usize;
+            }
+        }
+        println!("HashSet<Point>:   {:?} ({found})", start.elapsed());
+
+        let start = Instant::now();
+        let mut found = 0;
+        for _ in 0..rounds {
+            let mut set = FxHashSet::default();
+            for point in points.iter() {
+                set.insert(*point);
+                found += set.contains(&point.unbound_neighbour(Direction::Up)) as usize;
+            }
+        }
+        println!("FxHashSet<Point>: {:?} ({found})", start.elapsed());
+
+        let start = Instant::now();
+        let mut found = 0;
+        for _ in 0..rounds {
+            let mut set = PointSet::new(size, size);
+            for point in points.iter() {
+                set.insert(*point);
+                found += set.contains(&point.unbound_neighbour(Direction::Up)) as usize;
+            }
+        }
+        println!("PointSet:         {:?} ({found})", start.elapsed());
+    }
+
+    #[test]
+    fn test_topology() {
+        let torus = Bounds::new(11, 7, Topology::Torus);
+        let cylinder = Bounds::new(11, 7, Topology::Cylinder);
+        let corner = Point::new(0, 0);
+
+        assert_eq!(
+            Some(Point::new(6, 0)),
+            corner.neighbour_in(Direction::Up, &torus)
+        );
+        assert_eq!(None, corner.neighbour_in(Direction::Up, &cylinder));
+        assert_eq!(
+            Some(Point::new(0, 10)),
+            corner.neighbour_in(Direction::Left, &cylinder)
+        );
+        assert_eq!(None, corner.neighbour(Direction::Left, 11));
+
+        assert_eq!(Some(Point::new(1, 3)), torus.wrap(Point::new(-13, 25)));
+        assert_eq!(2, torus.distance(&corner, &Point::new(6, 10)));
+        assert_eq!(7, cylinder.distance(&corner, &Point::new(6, 10)));
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = Grid::from_lines(["abc", "def"].into_iter().map(String::from).collect());
+
+        let to_lines = |grid: &Grid<char>| {
+            grid.rows()
+                .map(|row| row.into_iter().collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["da", "eb", "fc"],
+            to_lines(&grid.transform(Transform::RotateClockwise))
+        );
+        assert_eq!(
+            vec!["fed", "cba"],
+            to_lines(&grid.transform(Transform::Rotate180))
+        );
+        assert_eq!(
+            vec!["cf", "be", "ad"],
+            to_lines(&grid.transform(Transform::RotateCounterClockwise))
+        );
+        assert_eq!(
+            vec!["def", "abc"],
+            to_lines(&grid.transform(Transform::FlipVertical))
+        );
+        assert_eq!(
+            vec!["ad", "be", "cf"],
+            to_lines(&grid.transform(Transform::Transpose))
+        );
+        assert_eq!(
+            vec!["fc", "eb", "da"],
+            to_lines(&grid.transform(Transform::AntiTranspose))
+        );
+
+        for transform in TRANSFORMS {
+            let transformed = grid.transform(transform);
+            assert_eq!(grid, transformed.transform(transform.inverse()));
+
+            // Point sets should move exactly like the cells do.
+            let points = PointSet::from_points(3, 2, [Point::new(0, 1), Point::new(1, 2)]);
+            let moved = points.transform(transform);
+
+            for point in points.iter() {
+                let new_point = transform.apply(point, 3, 2);
+                assert!(moved.contains(&new_point));
+                assert_eq!(grid[point], transformed[new_point]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = Grid::from_lines(["abc", "def"].into_iter().map(String::from).collect());
+
+        let to_strings = |lines: Vec<Vec<&char>>| {
+            lines
+                .into_iter()
+                .map(|line| line.into_iter().collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["ad", "be", "cf"], to_strings(grid.columns().collect()));
+        assert_eq!(
+            vec!["d", "ae", "bf", "c"],
+            to_strings(grid.diagonals().collect())
+        );
+        assert_eq!(
+            vec!["a", "bd", "ce", "f"],
+            to_strings(grid.anti_diagonals().collect())
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        let origin = Hex::new(0, 0);
+        let far = Hex::from_cube(3, -1, -2);
+
+        assert_eq!(3, origin.distance(&far));
+        assert_eq!(6, origin.neighbours().len());
+        assert!(origin
+            .neighbours()
+            .into_iter()
+            .all(|neighbour| origin.distance(&neighbour) == 1));
+
+        // Everything within 2 steps: 1 + 6 + 12.
+        let area = flood_fill(origin, Hex::neighbours, |hex| origin.distance(hex) <= 2);
+        assert_eq!(19, area.len());
+
+        let (path, length) = crate::search_utilities::dijkstra(
+            &origin,
+            |hex| hex.neighbours().into_iter().map(|neighbour| (neighbour, 1)),
+            |hex| *hex == far,
+        )
+        .unwrap();
+        assert_eq!(3, length);
+        assert_eq!(4, path.len());
+    }
+
+    #[test]
+    fn test_voxel() {
+        let origin = Voxel::new(0, 0, 0);
+
+        assert_eq!(6, origin.neighbours().len());
+        assert_eq!(26, origin.all_neighbours().len());
+
+        let in_cube = |voxel: &Voxel| [voxel.x, voxel.y, voxel.z].iter().all(|v| v.abs() <= 1);
+        assert_eq!(27, flood_fill(origin, Voxel::neighbours, in_cube).len());
+
+        // Only diagonally connected, so it takes the big neighbourhood to get across.
+        let diagonal =
+            |voxel: &Voxel| voxel.x == voxel.y && voxel.y == voxel.z && voxel.x.abs() <= 5;
+        assert_eq!(1, flood_fill(origin, Voxel::neighbours, diagonal).len());
+        assert_eq!(
+            11,
+            flood_fill(origin, Voxel::all_neighbours, diagonal).len()
+        );
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let garden = get_garden();
+        let region = garden.flood_fill(Point::new(1, 1), |plant| *plant == 'X');
+
+        assert_eq!(1, region.area());
+        assert_eq!(
+            Some((Point::new(1, 1), Point::new(1, 1))),
+            region.bounding_box()
+        );
+
+        let region = garden.flood_fill(Point::new(0, 0), |plant| *plant == 'O');
+        assert_eq!(
+            Some((Point::new(0, 0), Point::new(4, 4))),
+            region.bounding_box()
+        );
+    }
+}
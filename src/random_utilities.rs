@@ -0,0 +1,62 @@
+#![allow(dead_code)]
+
+// Pseudo-random, but the same every time for the same seed, without pulling in a crate for it.
+// It's xorshift, which is nowhere near good enough for anything but picking inputs to try.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // Xorshift never gets anywhere from 0.
+        assert_ne!(0, seed, "... nope.");
+
+        Self { state: seed }
+    }
+
+    // Anything below 2^bits. The highest bits are the best ones, so those are what's used.
+    pub fn get_bits(&mut self, bits: u32) -> u64 {
+        let value = self.next().unwrap();
+
+        value
+            .checked_shr(u64::BITS.saturating_sub(bits))
+            .unwrap_or(0)
+    }
+}
+
+impl Iterator for Random {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        Some(self.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_random() {
+        let numbers = Random::new(1).take(100).collect_vec();
+
+        assert_eq!(numbers, Random::new(1).take(100).collect_vec());
+        assert_ne!(numbers, Random::new(2).take(100).collect_vec());
+        assert_eq!(100, numbers.iter().unique().count());
+    }
+
+    #[test]
+    fn test_get_bits() {
+        let mut random = Random::new(1);
+
+        assert!((0..100).all(|_| random.get_bits(5) < 32));
+        assert!((0..100).any(|_| random.get_bits(5) >= 16));
+        assert_eq!(0, random.get_bits(0));
+        assert_ne!(0, random.get_bits(64));
+    }
+}
@@ -0,0 +1,241 @@
+#![allow(dead_code)]
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+// A set of disjoint, non-adjacent half-open ranges - anything touching gets merged on insert.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    // Start to end of every range.
+    ranges: BTreeMap<usize, usize>,
+    // Length to the starts of all ranges with that length, for the allocation queries.
+    starts_by_length: BTreeMap<usize, BTreeSet<usize>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add_span(&mut self, start: usize, end: usize) {
+        self.ranges.insert(start, end);
+        self.starts_by_length
+            .entry(end - start)
+            .or_default()
+            .insert(start);
+    }
+
+    fn remove_span(&mut self, start: usize) -> usize {
+        let end = self.ranges.remove(&start).unwrap();
+        let starts = self.starts_by_length.get_mut(&(end - start)).unwrap();
+
+        starts.remove(&start);
+
+        if starts.is_empty() {
+            self.starts_by_length.remove(&(end - start));
+        }
+
+        end
+    }
+
+    // Number of separate ranges, not number of values.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn total_length(&self) -> usize {
+        self.iter().map(|range| range.len()).sum()
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, end)| value < *end)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..*end)
+    }
+
+    pub fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let (mut start, mut end) = (range.start, range.end);
+
+        // Something starting before us that reaches (or just touches) our start swallows us...
+        if let Some((&previous_start, &previous_end)) = self.ranges.range(..=start).next_back() {
+            if previous_end >= start {
+                self.remove_span(previous_start);
+                start = previous_start;
+                end = end.max(previous_end);
+            }
+        }
+
+        // ... and so do we to anything starting inside us (or right at our end).
+        while let Some((&next_start, &next_end)) = self.ranges.range(start..=end).next() {
+            self.remove_span(next_start);
+            end = end.max(next_end);
+        }
+
+        self.add_span(start, end);
+    }
+
+    // Cuts the range out, splitting whatever it lands in the middle of.
+    pub fn remove(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let overlapping = self
+            .ranges
+            .range(..range.end)
+            .rev()
+            .take_while(|(_, end)| **end > range.start)
+            .map(|(start, end)| (*start, *end))
+            .collect::<Vec<_>>();
+
+        for (start, end) in overlapping {
+            self.remove_span(start);
+
+            if start < range.start {
+                self.add_span(start, range.start);
+            }
+
+            if end > range.end {
+                self.add_span(range.end, end);
+            }
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.extend(other.iter());
+        result
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+
+        for range in other.iter() {
+            result.remove(range);
+        }
+
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.difference(&self.difference(other))
+    }
+
+    // The leftmost range that's at least this long. Logarithmic for every distinct length that
+    // fits, which for puzzles like day 9 (lengths up to 9) is as good as logarithmic.
+    pub fn first_fit(&self, length: usize) -> Option<Range<usize>> {
+        self.starts_by_length
+            .range(length..)
+            .filter_map(|(_, starts)| starts.first())
+            .min()
+            .map(|start| *start..self.ranges[start])
+    }
+
+    // The shortest range that's at least this long, leftmost on ties.
+    pub fn best_fit(&self, length: usize) -> Option<Range<usize>> {
+        self.starts_by_length
+            .range(length..)
+            .next()
+            .map(|(_, starts)| *starts.first().unwrap())
+            .map(|start| start..self.ranges[&start])
+    }
+
+    // Takes the first `length` values of the first fitting range out of the set.
+    pub fn allocate_first_fit(&mut self, length: usize) -> Option<Range<usize>> {
+        let range = self.first_fit(length)?;
+        let allocated = range.start..range.start + length;
+
+        self.remove(allocated.clone());
+
+        Some(allocated)
+    }
+}
+
+impl Extend<Range<usize>> for RangeSet {
+    fn extend<I: IntoIterator<Item = Range<usize>>>(&mut self, ranges: I) {
+        for range in ranges {
+            self.insert(range);
+        }
+    }
+}
+
+impl FromIterator<Range<usize>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<usize>>>(ranges: I) -> Self {
+        let mut set = Self::new();
+        set.extend(ranges);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set: RangeSet = [0..3, 5..7, 10..12].into_iter().collect();
+        assert_eq!(3, set.len());
+        assert_eq!(7, set.total_length());
+
+        // Touching ranges merge, overlapping ones too.
+        set.insert(3..5);
+        set.insert(11..15);
+        assert_eq!(vec![0..7, 10..15], set.iter().collect::<Vec<_>>());
+
+        set.remove(2..4);
+        set.remove(9..11);
+        set.remove(14..20);
+        assert_eq!(vec![0..2, 4..7, 11..14], set.iter().collect::<Vec<_>>());
+
+        assert!(set.contains(0));
+        assert!(!set.contains(2));
+        assert!(set.contains(13));
+        assert!(!set.contains(14));
+
+        set.remove(0..100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let first: RangeSet = [0..5, 10..15].into_iter().collect();
+        let second: RangeSet = std::iter::once(3..12).collect();
+
+        assert_eq!(vec![0..15], first.union(&second).iter().collect::<Vec<_>>());
+        assert_eq!(
+            vec![0..3, 12..15],
+            first.difference(&second).iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![3..5, 10..12],
+            first.intersection(&second).iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_allocation() {
+        let mut set: RangeSet = [0..2, 4..10, 12..15].into_iter().collect();
+
+        assert_eq!(Some(4..10), set.first_fit(3));
+        assert_eq!(Some(12..15), set.best_fit(3));
+        assert_eq!(Some(0..2), set.best_fit(1));
+        assert_eq!(None, set.first_fit(7));
+
+        assert_eq!(Some(4..7), set.allocate_first_fit(3));
+        assert_eq!(Some(7..10), set.allocate_first_fit(3));
+        assert_eq!(Some(12..15), set.first_fit(3));
+        assert_eq!(vec![0..2, 12..15], set.iter().collect::<Vec<_>>());
+    }
+}
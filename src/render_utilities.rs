@@ -0,0 +1,370 @@
+#![allow(dead_code)]
+use std::fs;
+use std::path::Path;
+
+use itertools::Itertools;
+
+use crate::map_utilities::{Grid, Point};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Colour {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Colour {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    pub fn grey_level(&self) -> u8 {
+        // The usual luminance weights, so greyscale images still look right.
+        let level = 0.299 * self.red as f64 + 0.587 * self.green as f64 + 0.114 * self.blue as f64;
+        level.round() as u8
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+pub const BLACK: Colour = Colour::new(0, 0, 0);
+pub const WHITE: Colour = Colour::new(255, 255, 255);
+pub const GREY: Colour = Colour::new(128, 128, 128);
+pub const RED: Colour = Colour::new(220, 50, 50);
+pub const GREEN: Colour = Colour::new(50, 180, 50);
+pub const BLUE: Colour = Colour::new(50, 90, 220);
+pub const YELLOW: Colour = Colour::new(240, 200, 40);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Overlay {
+    points: Vec<Point>,
+    colour: Colour,
+    is_path: bool,
+}
+
+// A grid of coloured cells, with points and paths drawn on top of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Canvas {
+    cells: Grid<Colour>,
+    overlays: Vec<Overlay>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: Colour) -> Self {
+        Self {
+            cells: Grid::new(width, height, vec![background; width * height]),
+            overlays: vec![],
+        }
+    }
+
+    pub fn from_grid<T, F>(grid: &Grid<T>, palette: F) -> Self
+    where
+        F: Fn(&T) -> Colour,
+    {
+        let mut canvas = Self::new(grid.width, grid.height, BLACK);
+
+        for point in grid.points() {
+            canvas.cells[point] = palette(&grid[point]);
+        }
+
+        canvas
+    }
+
+    pub fn from_points<I>(
+        width: usize,
+        height: usize,
+        points: I,
+        colour: Colour,
+        background: Colour,
+    ) -> Self
+    where
+        I: IntoIterator<Item = Point>,
+    {
+        let mut canvas = Self::new(width, height, background);
+
+        for point in points {
+            canvas.fill(point, colour);
+        }
+
+        canvas
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height
+    }
+
+    // Points out of bounds are silently dropped - it's a picture, not a proof.
+    pub fn fill(&mut self, point: Point, colour: Colour) {
+        if let Some(cell) = self.cells.get_mut(point) {
+            *cell = colour;
+        }
+    }
+
+    pub fn add_points<I>(&mut self, points: I, colour: Colour)
+    where
+        I: IntoIterator<Item = Point>,
+    {
+        self.overlays.push(Overlay {
+            points: points.into_iter().collect(),
+            colour,
+            is_path: false,
+        });
+    }
+
+    // Consecutive points don't have to be neighbours, the segments between them are drawn as lines.
+    pub fn add_path(&mut self, path: &[Point], colour: Colour) {
+        self.overlays.push(Overlay {
+            points: path.to_vec(),
+            colour,
+            is_path: true,
+        });
+    }
+
+    // Bake the overlays into the cells, for the raster formats.
+    fn flatten(&self) -> Grid<Colour> {
+        let mut cells = self.cells.clone();
+
+        for overlay in self.overlays.iter() {
+            let points = if overlay.is_path && overlay.points.len() > 1 {
+                overlay
+                    .points
+                    .iter()
+                    .tuple_windows()
+                    .flat_map(|(from, to)| get_line(*from, *to))
+                    .collect_vec()
+            } else {
+                overlay.points.clone()
+            };
+
+            for point in points {
+                if let Some(cell) = cells.get_mut(point) {
+                    *cell = overlay.colour;
+                }
+            }
+        }
+
+        cells
+    }
+
+    fn get_scaled_pixels(&self, scale: usize) -> Vec<Colour> {
+        let cells = self.flatten();
+        let mut pixels = Vec::with_capacity(cells.width * cells.height * scale * scale);
+
+        for row in 0..cells.height * scale {
+            for column in 0..cells.width * scale {
+                let point = Point::new((row / scale) as isize, (column / scale) as isize);
+                pixels.push(cells[point]);
+            }
+        }
+
+        pixels
+    }
+
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let mut result = format!(
+            "P6\n{} {}\n255\n",
+            self.width() * scale,
+            self.height() * scale
+        )
+        .into_bytes();
+
+        for pixel in self.get_scaled_pixels(scale) {
+            result.extend([pixel.red, pixel.green, pixel.blue]);
+        }
+
+        result
+    }
+
+    pub fn to_pgm(&self, scale: usize) -> Vec<u8> {
+        let mut result = format!(
+            "P5\n{} {}\n255\n",
+            self.width() * scale,
+            self.height() * scale
+        )
+        .into_bytes();
+
+        result.extend(
+            self.get_scaled_pixels(scale)
+                .into_iter()
+                .map(|pixel| pixel.grey_level()),
+        );
+
+        result
+    }
+
+    pub fn get_svg_body(&self, scale: usize) -> Vec<String> {
+        let mut body = vec![];
+
+        for point in self.cells.points() {
+            body.push(format!(
+                r#"<rect x="{}" y="{}" width="{scale}" height="{scale}" fill="{}"/>"#,
+                point.column() * scale,
+                point.row() * scale,
+                self.cells[point].to_hex(),
+            ));
+        }
+
+        for overlay in self.overlays.iter() {
+            let centre = |point: &Point| {
+                let x = point.column as f64 * scale as f64 + scale as f64 / 2.0;
+                let y = point.row as f64 * scale as f64 + scale as f64 / 2.0;
+                format!("{x},{y}")
+            };
+
+            if overlay.is_path {
+                body.push(format!(
+                    r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+                    overlay.points.iter().map(centre).join(" "),
+                    overlay.colour.to_hex(),
+                    (scale as f64 / 3.0).max(1.0),
+                ));
+            } else {
+                for point in overlay.points.iter() {
+                    body.push(format!(
+                        r#"<rect x="{}" y="{}" width="{scale}" height="{scale}" fill="{}" fill-opacity="0.8"/>"#,
+                        point.column * scale as isize,
+                        point.row * scale as isize,
+                        overlay.colour.to_hex(),
+                    ));
+                }
+            }
+        }
+
+        body
+    }
+
+    pub fn get_svg_header(&self, scale: usize) -> String {
+        let width = self.width() * scale;
+        let height = self.height() * scale;
+
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+        )
+    }
+
+    pub fn to_svg(&self, scale: usize) -> String {
+        let mut lines = vec![self.get_svg_header(scale)];
+        lines.extend(self.get_svg_body(scale));
+        lines.push("</svg>".to_string());
+
+        lines.join("\n")
+    }
+
+    // Two spaces with a true colour background per cell, which looks roughly square in most terminals.
+    pub fn to_terminal(&self) -> String {
+        let cells = self.flatten();
+
+        (0..cells.height)
+            .map(|row| {
+                (0..cells.width)
+                    .map(|column| cells[Point::new(row as isize, column as isize)])
+                    .map(|colour| {
+                        format!(
+                            "\x1b[48;2;{};{};{}m  ",
+                            colour.red, colour.green, colour.blue
+                        )
+                    })
+                    .join("")
+                    + "\x1b[0m"
+            })
+            .join("\n")
+    }
+
+    // The format is picked by the file extension.
+    pub fn save<P>(&self, path: P, scale: usize)
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ppm") => self.to_ppm(scale),
+            Some("pgm") => self.to_pgm(scale),
+            Some("svg") => self.to_svg(scale).into_bytes(),
+            _ => panic!("I don't know how to render {path:?}..."),
+        };
+
+        fs::write(path, contents).unwrap();
+    }
+}
+
+// Bresenham's line, both ends included.
+pub fn get_line(from: Point, to: Point) -> Vec<Point> {
+    let delta_row = (to.row - from.row).abs();
+    let delta_column = (to.column - from.column).abs();
+    let step_row = (to.row - from.row).signum();
+    let step_column = (to.column - from.column).signum();
+
+    let mut error = delta_column - delta_row;
+    let mut current = from;
+    let mut line = vec![current];
+
+    while current != to {
+        let double_error = 2 * error;
+
+        if double_error > -delta_row {
+            error -= delta_row;
+            current.column += step_column;
+        }
+
+        if double_error < delta_column {
+            error += delta_column;
+            current.row += step_row;
+        }
+
+        line.push(current);
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ppm() {
+        let mut canvas = Canvas::from_points(3, 2, [Point::new(0, 0)], WHITE, BLACK);
+        canvas.add_path(&[Point::new(1, 0), Point::new(1, 2)], RED);
+
+        let ppm = canvas.to_ppm(1);
+        let header = b"P6\n3 2\n255\n";
+
+        assert_eq!(header.len() + 3 * 6, ppm.len());
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(
+            [255, 255, 255, 0, 0, 0],
+            ppm[header.len()..header.len() + 6]
+        );
+        assert_eq!([220, 50, 50], ppm[ppm.len() - 3..]);
+
+        let pgm = canvas.to_pgm(2);
+        assert_eq!(b"P5\n6 4\n255\n".len() + 24, pgm.len());
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut canvas = Canvas::new(2, 2, WHITE);
+        canvas.add_path(&[Point::new(0, 0), Point::new(1, 1)], BLUE);
+
+        let svg = canvas.to_svg(10);
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(4, svg.matches("<rect").count());
+        assert!(svg.contains(r#"points="5,5 15,15""#));
+    }
+
+    #[test]
+    fn test_line() {
+        assert_eq!(
+            vec![Point::new(0, 0), Point::new(0, 1), Point::new(1, 2)],
+            get_line(Point::new(0, 0), Point::new(1, 2))
+        );
+    }
+}
@@ -0,0 +1,141 @@
+#![allow(dead_code)]
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+// A day's parsed input, loaded once and then poked at one command at a time.
+pub trait Session {
+    // One line per command, e.g. "run <a> - runs the program with register A set".
+    fn get_help(&self) -> Vec<&'static str>;
+
+    // Mistakes in the command are errors rather than panics, so the session survives them.
+    fn handle(&mut self, command: &str, arguments: &[&str]) -> Result<String, String>;
+}
+
+pub fn parse_argument<T>(arguments: &[&str], index: usize, name: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let argument = arguments
+        .get(index)
+        .ok_or_else(|| format!("Missing argument <{name}>."))?;
+
+    argument
+        .parse()
+        .map_err(|error| format!("Can't use {argument:?} as <{name}>: {error}."))
+}
+
+pub fn parse_optional_argument<T>(
+    arguments: &[&str],
+    index: usize,
+    name: &str,
+    default: T,
+) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if index < arguments.len() {
+        parse_argument(arguments, index, name)
+    } else {
+        Ok(default)
+    }
+}
+
+// Built in: help, quit (or exit). Everything else goes to the session.
+pub fn run_session<S, R, W>(session: &mut S, input: R, output: &mut W)
+where
+    S: Session,
+    R: BufRead,
+    W: Write,
+{
+    write!(output, "> ").unwrap();
+    output.flush().unwrap();
+
+    for line in input.lines() {
+        let line = line.unwrap();
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        match words.split_first() {
+            None => {}
+            Some((&"quit", _)) | Some((&"exit", _)) => break,
+            Some((&"help", _)) => {
+                for help in session.get_help() {
+                    writeln!(output, "{help}").unwrap();
+                }
+
+                writeln!(output, "help - shows this").unwrap();
+                writeln!(output, "quit - leaves").unwrap();
+            }
+            Some((command, arguments)) => match session.handle(command, arguments) {
+                Ok(result) if result.is_empty() => {}
+                Ok(result) => writeln!(output, "{result}").unwrap(),
+                Err(error) => writeln!(output, "Error: {error}").unwrap(),
+            },
+        }
+
+        write!(output, "> ").unwrap();
+        output.flush().unwrap();
+    }
+
+    writeln!(output).unwrap();
+}
+
+pub fn run_interactive<S>(session: &mut S)
+where
+    S: Session,
+{
+    run_session(session, io::stdin().lock(), &mut io::stdout());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter {
+        count: i32,
+    }
+
+    impl Session for Counter {
+        fn get_help(&self) -> Vec<&'static str> {
+            vec!["add <n> - adds n to the count"]
+        }
+
+        fn handle(&mut self, command: &str, arguments: &[&str]) -> Result<String, String> {
+            match command {
+                "add" => {
+                    self.count += parse_argument::<i32>(arguments, 0, "n")?;
+                    Ok(self.count.to_string())
+                }
+                _ => Err(format!("Unknown command {command}.")),
+            }
+        }
+    }
+
+    #[test]
+    fn test_session() {
+        let mut counter = Counter { count: 0 };
+        let mut output = vec![];
+
+        run_session(
+            &mut counter,
+            "add 3\n\nadd\nadd x\nnope\nhelp\nadd 4\nquit\nadd 100\n".as_bytes(),
+            &mut output,
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        let lines = output
+            .lines()
+            .map(|line| line.trim_start_matches("> "))
+            .collect::<Vec<_>>();
+
+        assert_eq!("3", lines[0]);
+        assert_eq!("Error: Missing argument <n>.", lines[1]);
+        assert!(lines[2].starts_with(r#"Error: Can't use "x" as <n>"#));
+        assert_eq!("Error: Unknown command nope.", lines[3]);
+        assert_eq!("add <n> - adds n to the count", lines[4]);
+        assert_eq!("7", lines[7]);
+        assert_eq!(7, counter.count);
+    }
+}
@@ -0,0 +1,256 @@
+#![allow(dead_code)]
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+// A path through the search space, with the cumulative cost at every node along it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CostedPath<N> {
+    nodes: Vec<N>,
+    costs: Vec<usize>,
+}
+
+impl<N> CostedPath<N> {
+    fn total_cost(&self) -> usize {
+        *self.costs.last().unwrap()
+    }
+}
+
+fn dijkstra_path<N, FN, IN, FS>(
+    start: &N,
+    successors: &mut FN,
+    success: &FS,
+) -> Option<CostedPath<N>>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FS: Fn(&N) -> bool,
+{
+    // Nodes are stored once, and the heap only deals with indices into this list.
+    let mut nodes: Vec<(N, Option<usize>, usize)> = vec![(start.clone(), None, 0)];
+    let mut best_costs: HashMap<N, usize> = HashMap::new();
+    let mut settled = HashSet::new();
+
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((0, 0)));
+    best_costs.insert(start.clone(), 0);
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        let node = nodes[index].0.clone();
+
+        if !settled.insert(node.clone()) {
+            continue;
+        }
+
+        if success(&node) {
+            let mut path = vec![];
+            let mut current = Some(index);
+
+            while let Some(current_index) = current {
+                let (node, parent, cost) = &nodes[current_index];
+                path.push((node.clone(), *cost));
+                current = *parent;
+            }
+
+            path.reverse();
+            let (nodes, costs) = path.into_iter().unzip();
+
+            return Some(CostedPath { nodes, costs });
+        }
+
+        for (successor, move_cost) in successors(&node) {
+            if settled.contains(&successor) {
+                continue;
+            }
+
+            let new_cost = cost + move_cost;
+
+            if let Some(best_known_cost) = best_costs.get(&successor) {
+                if *best_known_cost <= new_cost {
+                    continue;
+                }
+            }
+
+            best_costs.insert(successor.clone(), new_cost);
+            nodes.push((successor, Some(index), new_cost));
+            heap.push(Reverse((new_cost, nodes.len() - 1)));
+        }
+    }
+
+    None
+}
+
+pub fn dijkstra<N, FN, IN, FS>(
+    start: &N,
+    mut successors: FN,
+    success: FS,
+) -> Option<(Vec<N>, usize)>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FS: Fn(&N) -> bool,
+{
+    dijkstra_path(start, &mut successors, &success).map(|path| {
+        let cost = path.total_cost();
+        (path.nodes, cost)
+    })
+}
+
+// Yen's algorithm - the k cheapest simple (no repeated node) paths from start to any success node,
+// cheapest first. Returns fewer than k paths if there aren't that many.
+pub fn k_shortest_paths<N, FN, IN, FS>(
+    start: &N,
+    successors: FN,
+    success: FS,
+    k: usize,
+) -> Vec<(Vec<N>, usize)>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FS: Fn(&N) -> bool,
+{
+    k_shortest_paths_by_key(start, successors, success, N::clone, k)
+}
+
+// The same, but a path only counts as simple if no key repeats, for when several nodes are the
+// same place - like a tile faced in different directions. Only the root is checked, the spur is
+// still a plain cheapest path, so this relies on coming back to a key never being cheaper.
+pub fn k_shortest_paths_by_key<N, K, FN, IN, FS, FK>(
+    start: &N,
+    mut successors: FN,
+    success: FS,
+    key: FK,
+    k: usize,
+) -> Vec<(Vec<N>, usize)>
+where
+    N: Clone + Eq + Hash,
+    K: Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FS: Fn(&N) -> bool,
+    FK: Fn(&N) -> K,
+{
+    let mut found: Vec<CostedPath<N>> = vec![];
+    let mut candidates: Vec<CostedPath<N>> = vec![];
+
+    if k == 0 {
+        return vec![];
+    }
+
+    match dijkstra_path(start, &mut successors, &success) {
+        Some(path) => found.push(path),
+        None => return vec![],
+    }
+
+    while found.len() < k {
+        let previous = found.last().unwrap().clone();
+
+        for spur_index in 0..previous.nodes.len() - 1 {
+            let spur_node = &previous.nodes[spur_index];
+            let root = &previous.nodes[..=spur_index];
+            let root_cost = previous.costs[spur_index];
+
+            // Don't allow leaving the root the same way an already known path did...
+            let banned_edges: HashSet<N> = found
+                .iter()
+                .filter(|path| {
+                    path.nodes.len() > spur_index + 1 && path.nodes[..=spur_index] == *root
+                })
+                .map(|path| path.nodes[spur_index + 1].clone())
+                .collect();
+
+            // ... and don't allow going back through the root, so the path stays simple.
+            let banned_keys: HashSet<K> = root.iter().map(&key).collect();
+
+            let mut spur_successors = |node: &N| {
+                let is_spur_node = node == spur_node;
+
+                successors(node)
+                    .into_iter()
+                    .filter(|(successor, _)| !banned_keys.contains(&key(successor)))
+                    .filter(|(successor, _)| !is_spur_node || !banned_edges.contains(successor))
+                    .collect::<Vec<_>>()
+            };
+
+            let Some(spur_path) = dijkstra_path(spur_node, &mut spur_successors, &success) else {
+                continue;
+            };
+
+            let mut nodes = root.to_vec();
+            nodes.extend(spur_path.nodes.into_iter().skip(1));
+
+            let mut costs = previous.costs[..=spur_index].to_vec();
+            costs.extend(
+                spur_path
+                    .costs
+                    .into_iter()
+                    .skip(1)
+                    .map(|cost| root_cost + cost),
+            );
+
+            let candidate = CostedPath { nodes, costs };
+
+            if !candidates.contains(&candidate) && !found.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+
+        let Some(best_index) =
+            (0..candidates.len()).min_by_key(|index| candidates[*index].total_cost())
+        else {
+            break;
+        };
+
+        found.push(candidates.swap_remove(best_index));
+    }
+
+    found
+        .into_iter()
+        .map(|path| {
+            let cost = path.total_cost();
+            (path.nodes, cost)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A small diamond: 0 -> 1 -> 3 (cost 2), 0 -> 2 -> 3 (cost 3), 0 -> 3 (cost 10).
+    fn diamond(node: &usize) -> Vec<(usize, usize)> {
+        match node {
+            0 => vec![(1, 1), (2, 1), (3, 10)],
+            1 => vec![(3, 1), (2, 5)],
+            2 => vec![(3, 2)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        assert_eq!(
+            Some((vec![0, 1, 3], 2)),
+            dijkstra(&0, diamond, |node| *node == 3)
+        );
+        assert_eq!(None, dijkstra(&3, diamond, |node| *node == 0));
+    }
+
+    #[test]
+    fn test_k_shortest_paths() {
+        let paths = k_shortest_paths(&0, diamond, |node| *node == 3, 10);
+
+        assert_eq!(
+            vec![
+                (vec![0, 1, 3], 2),
+                (vec![0, 2, 3], 3),
+                (vec![0, 1, 2, 3], 8),
+                (vec![0, 3], 10),
+            ],
+            paths
+        );
+    }
+}